@@ -9,7 +9,7 @@
 //! This crate is custom derive for ClapMe. It should not be used
 //! directly.
 
-#![recursion_limit="256"]
+#![recursion_limit="512"]
 
 extern crate proc_macro;
 extern crate syn;
@@ -19,8 +19,10 @@ extern crate proc_macro2;
 
 use syn::*;
 
-fn get_doc_comment(attrs: &[syn::Attribute]) -> String {
-    let mut doc_comments: Vec<_> = attrs
+/// The paragraphs of a doc comment, with the lines of each paragraph
+/// joined by spaces.
+fn doc_paragraphs(attrs: &[syn::Attribute]) -> Vec<String> {
+    let lines: Vec<_> = attrs
         .iter()
         .filter_map(|attr| {
             let path = &attr.path;
@@ -39,163 +41,690 @@ fn get_doc_comment(attrs: &[syn::Attribute]) -> String {
                 }
                 let value = s.value();
                 let text = value
-                    .trim_left_matches("//!")
-                    .trim_left_matches("///")
-                    .trim_left_matches("/*!")
-                    .trim_left_matches("/**")
-                    .trim_right_matches("*/")
+                    .trim_start_matches("//!")
+                    .trim_start_matches("///")
+                    .trim_start_matches("/*!")
+                    .trim_start_matches("/**")
+                    .trim_end_matches("*/")
                     .trim();
-                if text.is_empty() {
-                    Some("\n\n".to_string())
-                } else {
-                    Some(text.to_string())
-                }
+                Some(text.to_string())
             } else {
                 None
             }
         })
         .collect();
-    if doc_comments.len() > 0 {
-        doc_comments.pop().unwrap_or("".to_string())
+    let mut paragraphs = Vec::new();
+    let mut paragraph = String::new();
+    for line in lines {
+        if line.is_empty() {
+            if !paragraph.is_empty() {
+                paragraphs.push(paragraph);
+                paragraph = String::new();
+            }
+        } else {
+            if !paragraph.is_empty() {
+                paragraph.push(' ');
+            }
+            paragraph.push_str(&line);
+        }
+    }
+    if !paragraph.is_empty() {
+        paragraphs.push(paragraph);
+    }
+    paragraphs
+}
+
+/// The first paragraph of a doc comment, which is used as the short
+/// help.
+fn get_doc_comment(attrs: &[syn::Attribute]) -> String {
+    doc_paragraphs(attrs).into_iter().next().unwrap_or_default()
+}
+
+/// The entire doc comment, which is used as the long help.  This is
+/// empty if the doc comment has only one paragraph, since then the
+/// long help would be no different.
+fn get_long_doc_comment(attrs: &[syn::Attribute]) -> String {
+    let paragraphs = doc_paragraphs(attrs);
+    if paragraphs.len() > 1 {
+        paragraphs.join("\n\n")
+    } else {
+        "".to_string()
+    }
+}
+
+/// The settings given in `#[clapme(...)]` attributes.
+#[derive(Default)]
+struct Attrs {
+    short: Option<char>,
+    long: Option<String>,
+    rename: Option<String>,
+    rename_all: Option<String>,
+    aliases: Vec<String>,
+    default: Option<proc_macro2::TokenStream>,
+    default_value: Option<String>,
+    env: Option<String>,
+    env_prefix: Option<String>,
+    subcommand: bool,
+    positional: bool,
+    negatable: bool,
+    value_enum: bool,
+    duplicates: Option<bool>,
+    delimiter: Option<String>,
+    min_values: Option<u64>,
+    max_values: Option<u64>,
+}
+
+fn get_attrs(attrs: &[syn::Attribute]) -> Attrs {
+    let mut out = Attrs::default();
+    for meta in attrs.iter().filter_map(|a| a.interpret_meta()) {
+        let nested = match meta {
+            Meta::List(MetaList { ref ident, ref nested, .. }) if ident == "clapme" => {
+                nested.clone()
+            },
+            _ => continue,
+        };
+        for n in nested {
+            match n {
+                NestedMeta::Meta(Meta::NameValue(MetaNameValue { ident, lit, .. })) => {
+                    match (ident.to_string().as_ref(), lit) {
+                        ("short", Lit::Char(c)) => out.short = Some(c.value()),
+                        ("short", Lit::Str(ref s)) if s.value().chars().count() == 1 => {
+                            out.short = s.value().chars().next();
+                        },
+                        ("long", Lit::Str(s)) => out.long = Some(s.value()),
+                        ("rename", Lit::Str(s)) => out.rename = Some(s.value()),
+                        ("rename_all", Lit::Str(s)) => out.rename_all = Some(s.value()),
+                        ("alias", Lit::Str(s)) => out.aliases.push(s.value()),
+                        ("env", Lit::Str(s)) => out.env = Some(s.value()),
+                        ("env_prefix", Lit::Str(s)) => out.env_prefix = Some(s.value()),
+                        ("default", Lit::Str(s)) => {
+                            out.default = Some(s.value().parse()
+                                               .expect("clapme default must be an expression"));
+                        },
+                        ("default_value", Lit::Str(s)) => out.default_value = Some(s.value()),
+                        ("duplicates", Lit::Str(s)) => {
+                            out.duplicates = match s.value().as_ref() {
+                                "error" => Some(true),
+                                "warn" => Some(false),
+                                "last" => None,
+                                d => panic!("clapme duplicates must be error, warn or last, not {}", d),
+                            };
+                        },
+                        ("delimiter", Lit::Char(c)) => out.delimiter = Some(c.value().to_string()),
+                        ("delimiter", Lit::Str(s)) => out.delimiter = Some(s.value()),
+                        ("min_values", Lit::Int(n)) => out.min_values = Some(n.value()),
+                        ("max_values", Lit::Int(n)) => out.max_values = Some(n.value()),
+                        ("default", lit) => {
+                            out.default = Some(quote!(#lit));
+                        },
+                        (name, _) => panic!("Invalid clapme attribute: {}", name),
+                    }
+                },
+                NestedMeta::Meta(Meta::Word(ref ident)) if ident == "default" => {
+                    out.default = Some(quote!(::std::default::Default::default()));
+                },
+                NestedMeta::Meta(Meta::Word(ref ident)) if ident == "subcommand" => {
+                    out.subcommand = true;
+                },
+                NestedMeta::Meta(Meta::Word(ref ident)) if ident == "positional" => {
+                    out.positional = true;
+                },
+                NestedMeta::Meta(Meta::Word(ref ident)) if ident == "negatable" => {
+                    out.negatable = true;
+                },
+                NestedMeta::Meta(Meta::Word(ref ident)) if ident == "value_enum" => {
+                    out.value_enum = true;
+                },
+                NestedMeta::Meta(Meta::List(MetaList { ref ident, ref nested, .. }))
+                    if ident == "aliases" =>
+                {
+                    for a in nested.iter() {
+                        if let NestedMeta::Literal(Lit::Str(ref s)) = *a {
+                            out.aliases.push(s.value());
+                        } else {
+                            panic!("clapme aliases must be strings");
+                        }
+                    }
+                },
+                n => panic!("Invalid clapme attribute: {}", quote!(#n)),
+            }
+        }
+    }
+    out
+}
+
+/// The type used to set up the argument for a field.  A field with a
+/// default is handled as an `Option`, which is then unwrapped to the
+/// default value, except for a `bool`, which is already optional and
+/// remains a plain flag.
+fn field_type(f: &syn::Field) -> proc_macro2::TokenStream {
+    let ty = &f.ty;
+    let attrs = get_attrs(&f.attrs);
+    if (attrs.default.is_some() || attrs.default_value.is_some()) && !is_bool(f) {
+        quote!(Option<#ty>)
+    } else {
+        quote!(#ty)
+    }
+}
+
+/// Is the field a `bool`?
+fn is_bool(f: &syn::Field) -> bool {
+    let ty = &f.ty;
+    quote!(#ty).to_string() == "bool"
+}
+
+/// The expression that parses a field from the `ArgMatches`.
+fn field_from_clap(f: &syn::Field, argname: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let ty = &f.ty;
+    let attrs = get_attrs(&f.attrs);
+    let value = match (attrs.default_value, attrs.default) {
+        (Some(d), _) => quote! {
+            match <#ty as ::clapme::ClapMe>::from_clap_optional(&#argname, _matches)? {
+                Some(x) => x,
+                None => ::clapme::parse_default_value(&#argname, #d)?,
+            }
+        },
+        (None, Some(default)) => quote! {
+            <#ty as ::clapme::ClapMe>::from_clap_optional(&#argname, _matches)?
+                .unwrap_or_else(|| #default)
+        },
+        (None, None) => quote!(<#ty as ::clapme::ClapMe>::from_clap(&#argname, _matches)?),
+    };
+    match attrs.duplicates {
+        Some(error) => quote! {{
+            ::clapme::check_duplicates::<#ty>(&#argname, _matches, #error)?;
+            #value
+        }},
+        None => value,
+    }
+}
+
+/// The expression for the text showing the default of a field in its
+/// help, which is empty if it has no default.  A `default_value` is
+/// shown as it was written, while any other default is found when
+/// the help is created, and shown as a user would type it.
+fn field_default_help(f: &syn::Field) -> proc_macro2::TokenStream {
+    let ty = &f.ty;
+    let attrs = get_attrs(&f.attrs);
+    match (attrs.default_value, attrs.default) {
+        (Some(d), _) => quote!(format!(" [default: {}]", #d)),
+        (None, Some(default)) => quote!({
+            let d: #ty = #default;
+            let d = ::clapme::ClapMe::to_help_value(&d);
+            if d.is_empty() { String::new() } else { format!(" [default: {}]", d) }
+        }),
+        (None, None) => quote!(String::new()),
+    }
+}
+
+/// The expression for the name of the argument for a field, which
+/// is the long flag (if any), or else the field name appended to the
+/// prefix.
+fn field_argname(f: &syn::Field, rename_all: &Option<String>) -> proc_macro2::TokenStream {
+    if let Some(long) = get_attrs(&f.attrs).long {
+        return quote!(#long.to_string());
+    }
+    let name = field_name(f, rename_all);
+    quote!(join_prefix(&_prefix, #name))
+}
+
+/// The name of a field within the names of its arguments, which is
+/// empty for a field starting with `_`.
+fn field_name(f: &syn::Field, rename_all: &Option<String>) -> String {
+    let attrs = get_attrs(&f.attrs);
+    let ident = f.ident.clone().unwrap().to_string();
+    if let Some(rename) = attrs.rename {
+        rename
+    } else if ident.starts_with('_') {
+        "".to_string()
+    } else if let Some(ref style) = *rename_all {
+        rename_with(&ident, style)
     } else {
+        snake_case_to_kebab(&ident)
+    }
+}
+
+/// Does the type of a field hold the type `name` being derived, as a
+/// tree holds an `Option<Box<Self>>`?
+fn is_recursive(f: &syn::Field, name: &syn::Ident) -> bool {
+    fn mentions(tokens: proc_macro2::TokenStream, name: &syn::Ident) -> bool {
+        tokens.into_iter().any(|t| match t {
+            proc_macro2::TokenTree::Ident(ref i) => i == name || i == "Self",
+            proc_macro2::TokenTree::Group(ref g) => mentions(g.stream(), name),
+            _ => false,
+        })
+    }
+    let ty = &f.ty;
+    mentions(quote!(#ty), name)
+}
+
+/// The expression for whether a recursive field of the type `name`
+/// is nested too deeply to be given, where `argname` is its argument.
+/// This is `None` for a field that is not recursive.
+fn too_deep(f: &syn::Field, fields: &[syn::Field], name: &syn::Ident,
+            rename_all: &Option<String>, argname: proc_macro2::TokenStream)
+            -> Option<proc_macro2::TokenStream> {
+    if !is_recursive(f, name) || get_attrs(&f.attrs).long.is_some() {
+        return None;
+    }
+    let recursive: Vec<_> = fields.iter()
+        .filter(|x| is_recursive(x, name) && get_attrs(&x.attrs).long.is_none())
+        .map(|x| field_name(x, rename_all))
+        .filter(|x| !x.is_empty())
+        .collect();
+    Some(quote!(::clapme::too_deep(&#argname, &[#(#recursive),*])))
+}
+
+/// The expression for the name of the argument for an enum variant,
+/// analogous to `field_argname`.
+fn variant_argname(v: &syn::Variant, rename_all: &Option<String>) -> proc_macro2::TokenStream {
+    let attrs = get_attrs(&v.attrs);
+    if let Some(long) = attrs.long {
+        return quote!(#long.to_string());
+    }
+    let ident = v.ident.to_string();
+    let name = if let Some(rename) = attrs.rename {
+        rename
+    } else if ident.starts_with('_') {
         "".to_string()
+    } else if let Some(ref style) = *rename_all {
+        rename_with(&ident, style)
+    } else {
+        camel_case_to_kebab(&ident)
+    };
+    quote!(join_prefix(&orig_prefix, #name))
+}
+
+/// The name of the subcommand for an enum variant, which ignores any
+/// prefix.
+fn variant_subcommand_name(v: &syn::Variant, rename_all: &Option<String>) -> String {
+    let attrs = get_attrs(&v.attrs);
+    let ident = v.ident.to_string();
+    if let Some(long) = attrs.long {
+        long
+    } else if let Some(rename) = attrs.rename {
+        rename
+    } else if let Some(ref style) = *rename_all {
+        rename_with(&ident, style)
+    } else {
+        camel_case_to_kebab(&ident)
     }
 }
 
-fn one_field_name(f: syn::Fields) -> proc_macro2::TokenStream {
+/// The expression for the name of one required flag (if any) for an
+/// enum variant, which tells us whether that variant was selected.
+fn one_field_name(f: syn::Fields, rename_all: &Option<String>) -> proc_macro2::TokenStream {
     let join_prefix = create_join_prefix();
     match f {
         syn::Fields::Named(ref fields) => {
             let f: Vec<_> = fields.named.clone().into_iter().collect();
-            let names = f.iter().map(|x| snake_case_to_kebab(&x.ident.clone().unwrap().to_string()));
-            let types = f.iter().map(|x| x.ty.clone());
+            let names = f.iter().map(|x| field_argname(x, rename_all));
+            let types = f.iter().map(field_type);
             quote! {
                 {
                     let mut flagname: Option<String> = None;
                     let join_prefix = #join_prefix;
                     #(
-                        let thisname = join_prefix(&_prefix, #names);
+                        let thisname = #names;
                         let reqs = <#types as ::clapme::ClapMe>::requires_flags(&thisname);
                         if let Some(x) = reqs.first() {
                             flagname = Some(x.clone());
                         }
                     )*
-                    flagname.expect("enum must have one required field!")
+                    flagname
                 }
             }
         },
         syn::Fields::Unit => {
             quote!{
-                _name.to_string()
+                Some(_name.to_string())
             }
         },
-        syn::Fields::Unnamed(ref unnamed) => {
+        syn::Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
             let f = unnamed.unnamed.iter().next().expect("we should have one field");
             let mytype = f.ty.clone();
-            quote!{{
-                let reqs = <#mytype as ::clapme::ClapMe>::requires_flags(&_name);
-                if let Some(x) = reqs.first() {
-                    x.clone()
-                } else {
-                    panic!("enum {:?} must have one required field!", _name)
-                }
-            }}
+            quote!{
+                <#mytype as ::clapme::ClapMe>::requires_flags(&_name).first().cloned()
+            }
         },
+        syn::Fields::Unnamed(_) => {
+            quote!{
+                Some(_name.to_string())
+            }
+        },
+    }
+}
+
+/// The expression for the flags that tell us whether an enum variant
+/// was selected, which are one required flag if it has any, and
+/// otherwise all of its flags.
+fn variant_flags(f: syn::Fields, self_ty: &syn::Ident,
+                 rename_all: &Option<String>) -> proc_macro2::TokenStream {
+    let one = one_field_name(f.clone(), rename_all);
+    let all = fields_arg_names(&f, self_ty, rename_all);
+    quote! {
+        match #one {
+            Some(x) => vec![x],
+            None => #all,
+        }
     }
 }
 
 fn return_with_fields(f: syn::Fields,
-                      name: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+                      name: proc_macro2::TokenStream, self_ty: &syn::Ident,
+                      rename_all: &Option<String>) -> proc_macro2::TokenStream {
     let join_prefix = create_join_prefix();
     match f {
         syn::Fields::Named(ref fields) => {
             let f: Vec<_> = fields.named.clone().into_iter().collect();
-            let names = f.iter().map(|x| snake_case_to_kebab(&x.ident.clone().unwrap().to_string()));
-            let types = f.iter().map(|x| x.ty.clone());
+            let values = f.iter().map(|x| {
+                let argname = field_argname(x, rename_all);
+                let value = field_from_clap(x, argname.clone());
+                match too_deep(x, &f, self_ty, rename_all, argname) {
+                    Some(deep) => quote! {
+                        if #deep { ::std::default::Default::default() } else { #value }
+                    },
+                    None => value,
+                }
+            });
             let idents = f.iter().map(|x| x.ident.clone().unwrap());
             quote! {
                 let join_prefix = #join_prefix;
-                return Some( #name {
-                    #( #idents:
-                        <#types as ::clapme::ClapMe>::from_clap(&join_prefix(&_prefix, #names),
-                                                                _matches)?,  )*
+                return Ok( #name {
+                    #( #idents: #values,  )*
                 });
             }
         },
         syn::Fields::Unit => {
-            quote!( return Some( #name ); )
+            quote!( return Ok( #name ); )
         },
         syn::Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
             let f = unnamed.unnamed.iter().next().expect("we should have one field");
             let mytype = f.ty.clone();
             quote!{
-                return Some( #name(<#mytype as ::clapme::ClapMe>::from_clap(&_name, _matches)? ) );
+                return Ok( #name(<#mytype as ::clapme::ClapMe>::from_clap(&_name, _matches)? ) );
+            }
+        },
+        syn::Fields::Unnamed(ref unnamed) => {
+            let num = unnamed.unnamed.len();
+            let types = unnamed.unnamed.iter().map(|f| f.ty.clone());
+            let positions = 0..num;
+            quote!{
+                let values = ::clapme::tuple_values(&_name, _matches, #num)?;
+                return Ok( #name(#( ::clapme::parse_position::<#types>(&_name, &values, #positions)? ),*) );
+            }
+        },
+    }
+}
+
+/// The expression for the names of the clap arguments for a set of
+/// fields, which are named from `_name` and `_prefix`.
+fn fields_arg_names(f: &syn::Fields, self_ty: &syn::Ident,
+                    rename_all: &Option<String>) -> proc_macro2::TokenStream {
+    let join_prefix = create_join_prefix();
+    match *f {
+        syn::Fields::Named(ref fields) => {
+            let all: Vec<_> = fields.named.iter().cloned().collect();
+            let f: Vec<_> = fields.named.iter()
+                .filter(|x| !get_attrs(&x.attrs).subcommand)
+                .collect();
+            let extend = f.iter().map(|x| {
+                let ty = field_type(x);
+                let argname = field_argname(x, rename_all);
+                let extend = quote! {
+                    names.extend(<#ty as ::clapme::ClapMe>::arg_names(&#argname));
+                };
+                match too_deep(x, &all, self_ty, rename_all, argname) {
+                    Some(deep) => quote!(if !#deep { #extend }),
+                    None => extend,
+                }
+            });
+            quote!{{
+                let join_prefix = #join_prefix;
+                let mut names: Vec<String> = Vec::new();
+                #( #extend )*
+                names
+            }}
+        },
+        syn::Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
+            let ty = &unnamed.unnamed.iter().next().unwrap().ty;
+            quote!(<#ty as ::clapme::ClapMe>::arg_names(&_name))
+        },
+        syn::Fields::Unit => quote!(Vec::new()),
+        _ => quote!(vec![_name.to_string()]),
+    }
+}
+
+/// The expression for the arguments giving a set of fields, where
+/// `access` gives a reference to each field.  Positional arguments
+/// come after the flags, and subcommands come last, so that they are
+/// not given any of our flags.
+fn fields_to_args(f: &[syn::Field], access: Vec<proc_macro2::TokenStream>,
+                  rename_all: &Option<String>) -> proc_macro2::TokenStream {
+    let join_prefix = create_join_prefix();
+    let mut flag_args = Vec::new();
+    let mut positional_args = Vec::new();
+    let mut subcommand_args = Vec::new();
+    for (x, a) in f.iter().zip(access) {
+        let ty = &x.ty;
+        let attrs = get_attrs(&x.attrs);
+        if attrs.subcommand {
+            subcommand_args.push(quote!{
+                args.extend(<#ty as ::clapme::ClapMe>::to_subcommand_args(#a));
+            });
+        } else if attrs.positional {
+            positional_args.push(quote!{
+                positional_args.extend(<#ty as ::clapme::ClapMe>::to_clap_args(#a, ""));
+            });
+        } else if is_negatable(&attrs) && is_bool(x) {
+            let argname = field_argname(x, rename_all);
+            flag_args.push(quote!{
+                let argname = #argname;
+                if *#a {
+                    args.push(::std::ffi::OsString::from(format!("--{}", argname)));
+                } else {
+                    args.push(::std::ffi::OsString::from(format!("--no-{}", argname)));
+                }
+            });
+        } else {
+            let argname = field_argname(x, rename_all);
+            flag_args.push(quote!{
+                args.extend(<#ty as ::clapme::ClapMe>::to_clap_args(#a, &#argname));
+            });
+        }
+    }
+    let positional_args = if positional_args.is_empty() {
+        quote!()
+    } else if subcommand_args.is_empty() {
+        quote!{
+            let mut positional_args: Vec<::std::ffi::OsString> = Vec::new();
+            #( #positional_args )*
+            if positional_args.iter().any(|a| a.to_string_lossy().starts_with('-')) {
+                args.push(::std::ffi::OsString::from("--"));
             }
+            args.extend(positional_args);
+        }
+    } else {
+        quote!{
+            let mut positional_args: Vec<::std::ffi::OsString> = Vec::new();
+            #( #positional_args )*
+            args.extend(positional_args);
+        }
+    };
+    quote!{{
+        #[allow(unused_variables)]
+        let join_prefix = #join_prefix;
+        let mut args: Vec<::std::ffi::OsString> = Vec::new();
+        #( #flag_args )*
+        #positional_args
+        #( #subcommand_args )*
+        args
+    }}
+}
+
+/// The tokens for an optional number.
+fn option_tokens(x: Option<u64>) -> proc_macro2::TokenStream {
+    match x {
+        Some(n) => quote!(Some(#n)),
+        None => quote!(None),
+    }
+}
+
+/// The pattern matching an enum variant, with named fields bound by
+/// reference using `field_binding`, and a single unnamed field bound
+/// to `clapme_field` (or several to `tuple_binding`).
+fn variant_pattern(name: &syn::Ident, v: &syn::Variant) -> proc_macro2::TokenStream {
+    let variant_name = &v.ident;
+    match v.fields {
+        syn::Fields::Named(ref fields) => {
+            let idents = fields.named.iter().map(|x| x.ident.clone().unwrap());
+            let bindings = fields.named.iter().map(|x| field_binding(x.ident.as_ref().unwrap()));
+            quote!(#name::#variant_name { #( #idents: ref #bindings ),* })
         },
-        _ => {
-            panic!("ClapMe only supports named fields so far!")
+        syn::Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
+            quote!(#name::#variant_name(ref clapme_field))
         },
+        syn::Fields::Unnamed(ref unnamed) => {
+            let bindings = (0..unnamed.unnamed.len()).map(tuple_binding);
+            quote!(#name::#variant_name(#( ref #bindings ),*))
+        },
+        syn::Fields::Unit => quote!(#name::#variant_name),
     }
 }
 
-fn with_clap_fields(f: syn::Fields, mdoc: Option<String>) -> proc_macro2::TokenStream {
+fn tuple_binding(i: usize) -> syn::Ident {
+    syn::Ident::new(&format!("clapme_field_{}", i), proc_macro2::Span::call_site())
+}
+
+fn field_binding(ident: &syn::Ident) -> syn::Ident {
+    syn::Ident::new(&format!("clapme_field_{}", ident), proc_macro2::Span::call_site())
+}
+
+/// The code to set up the arguments for a set of fields.  `mdoc` is
+/// the help and long help of an enum variant, if this is one.
+fn with_clap_fields(f: syn::Fields, mdoc: Option<(String, String)>, attrs: &Attrs,
+                    self_ty: &syn::Ident, rename_all: &Option<String>) -> proc_macro2::TokenStream {
     match f {
         syn::Fields::Named(ref fields) => {
             let f: Vec<_> = fields.named.clone().into_iter().collect();
-            let names = f.iter().rev().map(|x| snake_case_to_kebab(&x.ident.clone().unwrap().to_string()));
-            let types = f.iter().rev().map(|x| x.ty.clone());
+            let names = f.iter().rev().map(|x| field_argname(x, rename_all));
+            let with_claps = f.iter().rev().map(|x| {
+                let ty = field_type(x);
+                let with_clap = quote! {
+                    <#ty as ::clapme::ClapMe>::with_clap(newinfo, app, f)
+                };
+                // The rest of the arguments are boxed for a recursive
+                // field, so that its type does not grow with each level.
+                match too_deep(x, &f, self_ty, rename_all, quote!(argname)) {
+                    Some(deep) => quote! {
+                        if #deep {
+                            f(app)
+                        } else {
+                            let f: Box<dyn FnOnce(::clapme::clap::App) -> ClapMeT + '_>
+                                = Box::new(f);
+                            #with_clap
+                        }
+                    },
+                    None => with_clap,
+                }
+            });
             let names1 = names.clone();
-            let types1 = f.iter().rev().map(|x| x.ty.clone());
+            let types1 = f.iter().rev().map(field_type);
+            let field_attrs: Vec<_> = f.iter().rev().map(|x| get_attrs(&x.attrs)).collect();
+            let shorts = field_attrs.iter().map(|a| short_tokens(a, quote!(None)));
+            let aliases = field_attrs.iter().map(|a| aliases_tokens(a, quote!(&[])));
+            let envs = field_attrs.iter().map(|a| env_tokens(a, quote!(None)));
+            let subcommands = field_attrs.iter().map(|a| a.subcommand);
+            let positionals = field_attrs.iter().map(|a| a.positional);
+            let negatables = field_attrs.iter().map(is_negatable);
+            let delimiters = field_attrs.iter().map(|a| match a.delimiter {
+                Some(ref d) => quote!(Some(#d)),
+                None => quote!(None),
+            });
+            let min_values = field_attrs.iter().map(|a| option_tokens(a.min_values));
+            let max_values = field_attrs.iter().map(|a| option_tokens(a.max_values));
 
-            let docs: Vec<_> = f.iter().rev().map(|x| get_doc_comment(&x.attrs)).collect();
+            let default_helps = f.iter().rev().map(field_default_help);
+            let helps = f.iter().rev().map(|x| {
+                let d = get_doc_comment(&x.attrs);
+                quote!(format!("{}{}{}", #d, default_help, env_help).trim().to_string())
+            });
+            let long_helps = f.iter().rev().map(|x| {
+                let d = get_long_doc_comment(&x.attrs);
+                if d.is_empty() {
+                    quote!(String::new())
+                } else {
+                    quote!(format!("{}{}{}", #d, default_help, env_help))
+                }
+            });
             let join_prefix = create_join_prefix();
+            let env_name = create_env_name();
             quote!{
                 let join_prefix = #join_prefix;
+                let env_name = #env_name;
                 let mut flags: Vec<String> = Vec::new();
                 if !info.required {
                     // only add dependencies on flags required by this
                     // set of fields, but not absolutely required.
-                    #(flags.extend(<#types1 as ::clapme::ClapMe>::requires_flags(&join_prefix(&_prefix, #names1)));)*
+                    #(flags.extend(<#types1 as ::clapme::ClapMe>::requires_flags(&#names1));)*
                     // println!("   my flags are {:?}", flags);
                 }
                 let mut new_req: Vec<&str> = flags.iter().map(AsRef::as_ref).collect();
                 new_req.extend(info.required_flags);
 
-                #( let argname = join_prefix(&_prefix, #names);
+                #( let argname = #names;
                    let my_req: Vec<&str>
                    = new_req.iter().map(|&s| s).filter(|s| *s != argname).collect();
+                   let envname: Option<String> = #envs.or_else(|| env_name(info.env_prefix, &argname));
+                   let env_help = envname.as_ref().map(|e| format!(" [env: {}]", e))
+                       .unwrap_or_default();
+                   let default_help: String = #default_helps;
+                   let help = #helps;
+                   let long_help = #long_helps;
                    let newinfo = ::clapme::ArgInfo {
                        name: &argname,
-                       help: #docs,
+                       help: &help,
+                       long_help: &long_help,
+                       env: envname.as_ref().map(AsRef::as_ref),
+                       subcommand: #subcommands,
+                       positional: #positionals,
+                       negatable: #negatables || info.negatable,
+                       optional: false,
+                       delimiter: #delimiters,
+                       min_values: #min_values,
+                       max_values: #max_values,
+                       short: #shorts,
+                       aliases: #aliases,
                        required_flags: &my_req,
                        required_unless_one: info.required_unless_one.clone(),
                        conflicted_flags: info.conflicted_flags.clone(),
                        ..info
                    };
                    let f = |app: ::clapme::clap::App| {
-                       <#types as ::clapme::ClapMe>::with_clap(newinfo, app, f)
+                       #with_claps
                    };
                 )*
             }
         },
         syn::Fields::Unit => {
-            let doc = mdoc.unwrap_or("".to_string());
+            let (doc, long_doc) = mdoc.unwrap_or_default();
+            let short = short_tokens(attrs, quote!(None));
+            let aliases = aliases_tokens(attrs, quote!(&[]));
             quote!{
-                let newinfo = info.clone();
+                let newinfo = ::clapme::ArgInfo {
+                    name: &_name,
+                    help: #doc,
+                    long_help: #long_doc,
+                    short: #short,
+                    aliases: #aliases,
+                    ..info.clone()
+                };
                 let f = |app: ::clapme::clap::App| {
-                    let conflicts: Vec<_> = newinfo.conflicted_flags.iter().map(AsRef::as_ref).collect();
                     let ruo: Vec<_> = newinfo.required_unless_one.iter().map(AsRef::as_ref).collect();
                     if ruo.len() > 0 {
-                        f(app.arg(::clapme::clap::Arg::with_name(&_name).long(&_name)
-                                  .requires_all(newinfo.required_flags)
-                                  .conflicts_with_all(&conflicts)
-                                  .required_unless_one(&ruo)
-                                  .help(#doc)))
+                        f(app.arg(newinfo.arg().required_unless_one(&ruo)))
                     } else {
-                        f(app.arg(::clapme::clap::Arg::with_name(&_name).long(&_name)
-                                  .requires_all(newinfo.required_flags)
-                                  .conflicts_with_all(&conflicts)
-                                  .help(#doc)))
+                        f(app.arg(newinfo.arg()))
                     }
                 };
             }
@@ -203,11 +732,24 @@ fn with_clap_fields(f: syn::Fields, mdoc: Option<String>) -> proc_macro2::TokenS
         syn::Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() == 1 => {
             let f = unnamed.unnamed.iter().next().unwrap();
             let mytype = f.ty.clone();
-            let doc = mdoc.unwrap_or("".to_string());
+            let (doc, long_doc) = match mdoc {
+                Some((doc, long_doc)) => (quote!(#doc), quote!(#long_doc)),
+                None => (quote!(info.help), quote!(info.long_help)),
+            };
+            let short = short_tokens(attrs, quote!(info.short));
+            let aliases = aliases_tokens(attrs, quote!(info.aliases));
+            let env = env_tokens(attrs, quote!(info.env.map(|e| e.to_string())));
+            let env_name = create_env_name();
             quote!{
+                let env_name = #env_name;
+                let envname: Option<String> = #env.or_else(|| env_name(info.env_prefix, &_name));
                 let newinfo = ::clapme::ArgInfo {
                     name: &_name,
                     help: #doc,
+                    long_help: #long_doc,
+                    short: #short,
+                    aliases: #aliases,
+                    env: envname.as_ref().map(AsRef::as_ref),
                     required_flags: &info.required_flags,
                     required_unless_one: info.required_unless_one.clone(),
                     conflicted_flags: info.conflicted_flags.clone(),
@@ -218,12 +760,102 @@ fn with_clap_fields(f: syn::Fields, mdoc: Option<String>) -> proc_macro2::TokenS
                 };
             }
         },
-        _ => {
-            panic!("ClapMe only supports named fields, unit, and singular tuple so far!")
+        syn::Fields::Unnamed(ref unnamed) => {
+            let types = unnamed.unnamed.iter().map(|f| f.ty.clone());
+            let (doc, long_doc) = match mdoc {
+                Some((doc, long_doc)) => (quote!(#doc), quote!(#long_doc)),
+                None => (quote!(info.help), quote!(info.long_help)),
+            };
+            let short = short_tokens(attrs, quote!(info.short));
+            let aliases = aliases_tokens(attrs, quote!(info.aliases));
+            let env = env_tokens(attrs, quote!(info.env.map(|e| e.to_string())));
+            let env_name = create_env_name();
+            quote!{
+                let env_name = #env_name;
+                let envname: Option<String> = #env.or_else(|| env_name(info.env_prefix, &_name));
+                let value_names = [#( <#types as ::clapme::ClapMeValue>::value_name() ),*];
+                let newinfo = ::clapme::ArgInfo {
+                    name: &_name,
+                    help: #doc,
+                    long_help: #long_doc,
+                    short: #short,
+                    aliases: #aliases,
+                    env: envname.as_ref().map(AsRef::as_ref),
+                    required_flags: &info.required_flags,
+                    required_unless_one: info.required_unless_one.clone(),
+                    conflicted_flags: info.conflicted_flags.clone(),
+                    ..info
+                };
+                let f = |app: ::clapme::clap::App| {
+                    newinfo.with_arg(app, newinfo.tuple_arg(&value_names), f)
+                };
+            }
         },
     }
 }
 
+/// The `about` and `long_about` of an app or subcommand, from its doc
+/// comment.
+fn about_tokens(attrs: &[syn::Attribute]) -> proc_macro2::TokenStream {
+    let about = get_doc_comment(attrs);
+    let long_about = get_long_doc_comment(attrs);
+    match (about.is_empty(), long_about.is_empty()) {
+        (true, _) => quote!(),
+        (false, true) => quote!(.about(#about)),
+        (false, false) => quote!(.about(#about).long_about(#long_about)),
+    }
+}
+
+fn short_tokens(attrs: &Attrs, otherwise: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match attrs.short {
+        Some(c) => quote!(Some(#c)),
+        None => otherwise,
+    }
+}
+
+fn aliases_tokens(attrs: &Attrs, otherwise: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    if attrs.aliases.is_empty() {
+        otherwise
+    } else {
+        let aliases = &attrs.aliases;
+        quote!(&[#(#aliases),*])
+    }
+}
+
+/// Does a `bool` field get a `--no-` flag?  This is so if it is
+/// asked for, or if the field is true by default.
+fn is_negatable(attrs: &Attrs) -> bool {
+    attrs.negatable || attrs.default.as_ref().map(|d| d.to_string()) == Some("true".to_string())
+}
+
+fn env_tokens(attrs: &Attrs, otherwise: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    match attrs.env {
+        Some(ref e) => quote!(Some(#e.to_string())),
+        None => otherwise,
+    }
+}
+
+/// The environment variable for a flag, if there is an `env_prefix`.
+fn create_env_name() -> proc_macro2::TokenStream {
+    quote!{
+        |prefix: Option<&str>, name: &str| -> Option<String> {
+            match prefix {
+                Some(p) if name.len() > 0 => {
+                    Some(format!("{}_{}", p, name).to_uppercase().replace("-", "_"))
+                },
+                _ => None,
+            }
+        }
+    }
+}
+
+fn env_prefix_tokens(attrs: &Attrs) -> proc_macro2::TokenStream {
+    match attrs.env_prefix {
+        Some(ref p) => quote!(info.env_prefix = Some(#p);),
+        None => quote!(),
+    }
+}
+
 fn create_join_prefix() -> proc_macro2::TokenStream {
     quote!{
         |prefix: &str, name: &str| -> String {
@@ -249,13 +881,14 @@ fn create_find_prefix() -> proc_macro2::TokenStream {
 }
 
 /// Generates the `ClapMe` impl.
-#[proc_macro_derive(ClapMe)]
+#[proc_macro_derive(ClapMe, attributes(clapme))]
 pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     use syn::Data::*;
     let input: DeriveInput = syn::parse(raw_input.clone()).unwrap();
 
     let name = &input.ident;
     let generics = &input.generics;
+    let attrs = get_attrs(&input.attrs);
     let find_prefix = create_find_prefix();
     let join_prefix = create_join_prefix();
     let myimpl = match input.data {
@@ -264,24 +897,32 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             ..
         }) => {
             let f: Vec<_> = fields.named.clone().into_iter().collect();
-            let types3 = f.iter().rev().map(|x| x.ty.clone());
-            let names3 = f.iter().rev().map(|x| snake_case_to_kebab(&x.ident.clone().unwrap().to_string()));
+            let types3 = f.iter().rev().map(field_type);
+            let names3 = f.iter().rev().map(|x| field_argname(x, &attrs.rename_all));
             let with_clap_stuff = with_clap_fields(syn::Fields::Named(fields.clone()),
-                                                   None);
+                                                   None, &attrs, name, &attrs.rename_all);
             let return_struct = return_with_fields(syn::Fields::Named(fields.clone()),
-                                                   quote!(#name));
+                                                   quote!(#name), name, &attrs.rename_all);
+            let env_prefix = env_prefix_tokens(&attrs);
+            let access = f.iter().map(|x| {
+                let ident = x.ident.clone().unwrap();
+                quote!(&self.#ident)
+            }).collect();
+            let to_args = fields_to_args(&f, access, &attrs.rename_all);
+            let arg_names = fields_arg_names(&syn::Fields::Named(fields.clone()), name,
+                                             &attrs.rename_all);
             quote!{
                 fn with_clap<ClapMeT>(mut info: ::clapme::ArgInfo,
                                 app: ::clapme::clap::App,
                                 f: impl FnOnce(::clapme::clap::App) -> ClapMeT)
                                 -> ClapMeT {
-                    info.multiple = false;
+                    #env_prefix
                     let _name = info.name;
                     let _prefix = #find_prefix(_name);
                     #with_clap_stuff
                     f(app)
                 }
-                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Option<Self> {
+                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Result<Self, ::clapme::ClapMeError> {
                     let _prefix = #find_prefix(_name);
                     #return_struct
                 }
@@ -290,9 +931,17 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     let _prefix = #find_prefix(_name);
                     let mut flags: Vec<String> = Vec::new();
                     let join_prefix = #join_prefix;
-                    #(flags.extend(<#types3 as ::clapme::ClapMe>::requires_flags(&join_prefix(&_prefix, #names3)));)*;
+                    #(flags.extend(<#types3 as ::clapme::ClapMe>::requires_flags(&#names3));)*;
                     flags
                 }
+                fn arg_names(_name: &str) -> Vec<String> {
+                    let _prefix = #find_prefix(_name);
+                    #arg_names
+                }
+                fn to_clap_args(&self, _name: &str) -> Vec<::std::ffi::OsString> {
+                    let _prefix = #find_prefix(_name);
+                    #to_args
+                }
             }
         },
         Struct(DataStruct {
@@ -306,25 +955,56 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                                       -> ClapMeT {
                     f(app)
                 }
-                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Option<Self> {
-                    Some( #name )
+                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Result<Self, ::clapme::ClapMeError> {
+                    Ok( #name )
                 }
                 fn requires_flags(_name: &str) -> Vec<String> {
                     Vec::new()
                 }
+                fn arg_names(_name: &str) -> Vec<String> {
+                    Vec::new()
+                }
             }
         },
         Struct(DataStruct {
             fields: syn::Fields::Unnamed(ref unnamed),
             ..
-        }) => {
-            if unnamed.unnamed.len() != 1 {
-                panic!("ClapMe does not handle tuple structs with more than one field");
+        }) if unnamed.unnamed.len() > 1 => {
+            let with_clap_stuff = with_clap_fields(syn::Fields::Unnamed(unnamed.clone()),
+                                                   None, &attrs, name, &attrs.rename_all);
+            let env_prefix = env_prefix_tokens(&attrs);
+            let return_struct = return_with_fields(syn::Fields::Unnamed(unnamed.clone()),
+                                                   quote!(#name), name, &attrs.rename_all);
+            let positions = (0..unnamed.unnamed.len()).map(syn::Index::from);
+            quote!{
+                fn with_clap<ClapMeT>(mut info: ::clapme::ArgInfo,
+                                app: ::clapme::clap::App,
+                                f: impl FnOnce(::clapme::clap::App) -> ClapMeT)
+                                      -> ClapMeT {
+                    #env_prefix
+                    let _name = info.name;
+                    #with_clap_stuff
+                    f(app)
+                }
+                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Result<Self, ::clapme::ClapMeError> {
+                    #return_struct
+                }
+                fn to_clap_args(&self, _name: &str) -> Vec<::std::ffi::OsString> {
+                    ::clapme::tuple_to_args(_name, vec![
+                        #( ::clapme::ClapMeValue::to_value(&self.#positions) ),*
+                    ])
+                }
             }
+        },
+        Struct(DataStruct {
+            fields: syn::Fields::Unnamed(ref unnamed),
+            ..
+        }) => {
             let with_clap_stuff = with_clap_fields(syn::Fields::Unnamed(unnamed.clone()),
-                                                   None);
+                                                   None, &attrs, name, &attrs.rename_all);
+            let env_prefix = env_prefix_tokens(&attrs);
             let return_struct = return_with_fields(syn::Fields::Unnamed(unnamed.clone()),
-                                                   quote!(#name));
+                                                   quote!(#name), name, &attrs.rename_all);
             let f = unnamed.unnamed.iter().next().expect("There should be a field here!");
             let mytype = f.ty.clone();
             quote!{
@@ -332,21 +1012,73 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                                 app: ::clapme::clap::App,
                                 f: impl FnOnce(::clapme::clap::App) -> ClapMeT)
                                       -> ClapMeT {
+                    #env_prefix
                     let _name = info.name;
                     #with_clap_stuff
                     f(app)
                 }
-                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Option<Self> {
+                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Result<Self, ::clapme::ClapMeError> {
                     #return_struct
                 }
                 fn requires_flags(_name: &str) -> Vec<String> {
                     <#mytype as ::clapme::ClapMe>::requires_flags(_name)
                 }
+                fn arg_names(_name: &str) -> Vec<String> {
+                    <#mytype as ::clapme::ClapMe>::arg_names(_name)
+                }
+                fn to_clap_args(&self, _name: &str) -> Vec<::std::ffi::OsString> {
+                    self.0.to_clap_args(_name)
+                }
+                fn to_subcommand_args(&self) -> Vec<::std::ffi::OsString> {
+                    self.0.to_subcommand_args()
+                }
+            }
+        },
+        Enum(ref e) if attrs.value_enum => {
+            if !e.variants.iter().all(|v| matches!(v.fields, syn::Fields::Unit)) {
+                panic!("clapme(value_enum) requires an enum with only unit variants");
+            }
+            let docs: Vec<_> = e.variants.iter().map(|v| {
+                let doc = get_doc_comment(&v.attrs);
+                if doc.is_empty() {
+                    String::new()
+                } else {
+                    format!("    {}: {}", variant_subcommand_name(v, &attrs.rename_all), doc)
+                }
+            }).filter(|d| !d.is_empty()).collect();
+            let docs = docs.join("\n");
+            let values: Vec<_> = e.variants.iter()
+                .map(|v| variant_subcommand_name(v, &attrs.rename_all))
+                .collect();
+            quote!{
+                fn with_clap<ClapMeT>(info: ::clapme::ArgInfo,
+                                app: ::clapme::clap::App,
+                                f: impl FnOnce(::clapme::clap::App) -> ClapMeT)
+                                -> ClapMeT {
+                    let docs = #docs;
+                    let help = if info.long_help.is_empty() { info.help } else { info.long_help };
+                    let long_help = if docs.is_empty() || help.is_empty() {
+                        format!("{}{}", help, docs)
+                    } else {
+                        format!("{}\n\n{}", help, docs)
+                    };
+                    let info = ::clapme::ArgInfo { long_help: &long_help, ..info };
+                    info.with_arg(app, info.value_arg(<Self as ::clapme::ClapMeValue>::value_name())
+                                       .possible_values(&[#(#values),*])
+                                       .hide_possible_values(true)
+                                       .case_insensitive(true), f)
+                }
+                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Result<Self, ::clapme::ClapMeError> {
+                    ::clapme::value_from_clap(_name, _matches)
+                }
+                fn to_clap_args(&self, _name: &str) -> Vec<::std::ffi::OsString> {
+                    ::clapme::value_to_clap_args(self, _name)
+                }
             }
         },
         Enum(ref e) => {
             let v: Vec<_> = e.variants.iter().collect();
-            let vnames: Vec<_> = e.variants.iter().map(|v| camel_case_to_kebab(&v.ident.to_string())).collect();
+            let vnames: Vec<_> = e.variants.iter().map(|v| variant_argname(v, &attrs.rename_all)).collect();
             let only_one_variant = vnames.len() == 1;
             // If only_one_variant is true, this is a special case,
             // and the code below won't work, because required_unless
@@ -360,36 +1092,133 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
             let vnames6 = vnames.clone();
             // println!("variant names are {:?}", names);
             let fields: Vec<_> = v.iter().map(|x| x.fields.clone()).collect();
-            let with_claps: Vec<_> = v.iter().map(|v| {
-                let d = get_doc_comment(&v.attrs);
-                with_clap_fields(v.fields.clone(), Some(d))
+            let vattrs: Vec<_> = v.iter().map(|v| get_attrs(&v.attrs)).collect();
+            let with_claps: Vec<_> = v.iter().zip(vattrs.iter()).map(|(v, a)| {
+                let d = (get_doc_comment(&v.attrs), get_long_doc_comment(&v.attrs));
+                with_clap_fields(v.fields.clone(), Some(d), a, name, &a.rename_all)
             }).collect();
             // println!("variant with_claps are {:?}", with_claps);
-            let one_field: Vec<_> = fields.iter().map(|f| one_field_name(f.clone())).collect();
+            let one_field: Vec<_> = fields.iter().zip(vattrs.iter())
+                .map(|(f, a)| variant_flags(f.clone(), name, &a.rename_all)).collect();
             let one_field2 = one_field.clone();
             let one_field3 = one_field.clone();
-            let return_enum = v.iter().map(|v| {
+            let return_enum = v.iter().zip(vattrs.iter()).map(|(v, a)| {
                 let variant_name = v.ident.clone();
-                return_with_fields(v.fields.clone(), quote!(#name::#variant_name))
+                return_with_fields(v.fields.clone(), quote!(#name::#variant_name), name,
+                                   &a.rename_all)
+            });
+            let subnames: Vec<_> = v.iter().map(|v| variant_subcommand_name(v, &attrs.rename_all))
+                .collect();
+            let subnames2 = subnames.clone();
+            let subnames3 = subnames.clone();
+            let subnames4 = subnames.clone();
+            let sub_with_claps = v.iter().zip(vattrs.iter()).map(|(v, a)| {
+                let d = (get_doc_comment(&v.attrs), get_long_doc_comment(&v.attrs));
+                if let syn::Fields::Unit = v.fields {
+                    quote!()
+                } else {
+                    let fields = with_clap_fields(v.fields.clone(), Some(d), a, name,
+                                                  &a.rename_all);
+                    quote!{
+                        let mut info = ::clapme::ArgInfo::new("");
+                        info.env_prefix = env_prefix;
+                        info.sweep_flags = sweep_flags;
+                        info.param_flags = param_flags;
+                        info.subcommand = true;
+                        let _name = "";
+                        let _prefix = String::new();
+                        #fields
+                    }
+                }
             });
+            let subdocs = v.iter().map(|v| about_tokens(&v.attrs));
+            let return_sub = v.iter().zip(vattrs.iter()).map(|(v, a)| {
+                let variant_name = v.ident.clone();
+                return_with_fields(v.fields.clone(), quote!(#name::#variant_name), name,
+                                   &a.rename_all)
+            });
+            let subcommand_with_clap = quote!{
+                let env_prefix = info.env_prefix;
+                let sweep_flags = info.sweep_flags;
+                let param_flags = info.param_flags;
+                let required = info.required;
+                #( info.subcommand_named(#subnames4); )*
+                #(
+                    let f = |app: ::clapme::clap::App| {
+                        let f = |sub: ::clapme::clap::App| f(app.subcommand(sub));
+                        #sub_with_claps
+                        f(::clapme::clap::SubCommand::with_name(#subnames)#subdocs)
+                    };
+                )*
+                if required {
+                    f(app.setting(::clapme::clap::AppSettings::SubcommandRequired))
+                } else {
+                    f(app)
+                }
+            };
+            let patterns: Vec<_> = v.iter().map(|x| variant_pattern(name, x)).collect();
+            let patterns2 = patterns.clone();
+            let variant_args = |v: &syn::Variant, a: &Attrs, subcommand: bool| match v.fields {
+                syn::Fields::Named(ref fields) => {
+                    let f: Vec<_> = fields.named.iter().cloned().collect();
+                    let access = f.iter().map(|x| {
+                        let b = field_binding(x.ident.as_ref().unwrap());
+                        quote!(#b)
+                    }).collect();
+                    fields_to_args(&f, access, &a.rename_all)
+                },
+                syn::Fields::Unnamed(ref unnamed) if unnamed.unnamed.len() > 1 => {
+                    let bindings = (0..unnamed.unnamed.len()).map(tuple_binding);
+                    let name = if subcommand { quote!("") } else { quote!(&_name) };
+                    quote! {
+                        ::clapme::tuple_to_args(#name, vec![
+                            #( ::clapme::ClapMeValue::to_value(#bindings) ),*
+                        ])
+                    }
+                },
+                syn::Fields::Unnamed(_) if subcommand => quote!(clapme_field.to_subcommand_args()),
+                syn::Fields::Unnamed(_) => quote!(clapme_field.to_clap_args(&_name)),
+                syn::Fields::Unit if subcommand => quote!(Vec::new()),
+                syn::Fields::Unit => quote!(vec![::std::ffi::OsString::from(format!("--{}", _name))]),
+            };
+            let flag_args = v.iter().zip(vattrs.iter()).map(|(v, a)| variant_args(v, a, false));
+            let sub_args: Vec<_> = v.iter().zip(vattrs.iter())
+                .map(|(v, a)| variant_args(v, a, true)).collect();
+            let vnames7 = vnames.clone();
+            let to_clap_args = if attrs.subcommand {
+                quote!(self.to_subcommand_args())
+            } else {
+                quote!{
+                    let find_prefix = #find_prefix;
+                    let orig_prefix = find_prefix(_name);
+                    let join_prefix = #join_prefix;
+                    match *self {
+                        #( #patterns => {
+                            let _name = #vnames7;
+                            let _prefix = find_prefix(&_name);
+                            #flag_args
+                        } )*
+                    }
+                }
+            };
             let find_prefix = create_find_prefix();
-            let s = quote! {
-                fn with_clap<ClapMeT>(mut info: ::clapme::ArgInfo,
-                                app: ::clapme::clap::App,
-                                f: impl FnOnce(::clapme::clap::App) -> ClapMeT)
-                                -> ClapMeT {
+            let flag_with_clap = quote! {
                     let _name = info.name;
                     let find_prefix = #find_prefix;
                     let _prefix = find_prefix(_name);
                     let orig_prefix = _prefix.clone();
                     let join_prefix = #join_prefix;
                     info.multiple = false;
+                    info.short = None;
+                    info.aliases = &[];
+                    info.env = None;
+                    info.env_prefix = None;
 
                     let mut conflicts: Vec<String> = Vec::new();
                     #(
-                        let _name = join_prefix(&orig_prefix, #vnames3);
-                        let _prefix = find_prefix(&join_prefix(&orig_prefix, #vnames4));
-                        conflicts.push(#one_field2);
+                        let _name = #vnames3;
+                        let _prefix = find_prefix(&#vnames4);
+                        conflicts.extend(#one_field2);
                     )*
 
                     let original_conflicted = info.conflicted_flags.clone();
@@ -397,12 +1226,12 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     let am_required = info.required || original_required_unless.len() > 0;
                     info.required = #only_one_variant && am_required;
                     #(
-                        let _name = join_prefix(&orig_prefix, #vnames);
-                        let _prefix = find_prefix(&join_prefix(&orig_prefix, #vnames2));
+                        let _name = #vnames;
+                        let _prefix = find_prefix(&#vnames2);
                         let myself = #one_field3;
                         info.required_unless_one = original_required_unless.clone();
                         info.conflicted_flags = original_conflicted.clone();
-                        conflicts.iter().filter(|s| **s != myself).map(|s| {
+                        conflicts.iter().filter(|s| !myself.contains(s)).map(|s| {
                             info.conflicted_flags.push(s.clone());
                             if am_required {
                                 info.required_unless_one.push(s.clone());
@@ -414,21 +1243,58 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                         #with_claps
                     )*
                     f(app)
+            };
+            let with_clap_body = if attrs.subcommand {
+                subcommand_with_clap
+            } else {
+                quote!{
+                    if info.subcommand {
+                        #subcommand_with_clap
+                    } else {
+                        #flag_with_clap
+                    }
+                }
+            };
+            let s = quote! {
+                fn with_clap<ClapMeT>(mut info: ::clapme::ArgInfo,
+                                app: ::clapme::clap::App,
+                                f: impl FnOnce(::clapme::clap::App) -> ClapMeT)
+                                -> ClapMeT {
+                    #with_clap_body
                 }
-                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Option<Self> {
+                fn from_clap<'a,'b>(_name: &str, _matches: &::clapme::clap::ArgMatches) -> Result<Self, ::clapme::ClapMeError> {
+                    #(
+                        if let Some(_matches) = _matches.subcommand_matches(#subnames2) {
+                            let _name = "";
+                            let _prefix = String::new();
+                            #return_sub
+                        }
+                    )*
                     let find_prefix = #find_prefix;
                     let _prefix = find_prefix(_name);
+                    let orig_name = _name;
                     let orig_prefix = _prefix;
-                    let _join_prefix = #join_prefix;
+                    let join_prefix = #join_prefix;
                     #(
-                        let _name = _join_prefix(&orig_prefix, #vnames5);
-                        let _prefix = find_prefix(&_join_prefix(&orig_prefix, #vnames6));
+                        let _name = #vnames5;
+                        let _prefix = find_prefix(&#vnames6);
                         // println!("this is good: {:?} and {:?}", &name, &_prefix);
-                        if _matches.is_present(#one_field) {
+                        if #one_field.iter().any(|x| _matches.is_present(x)) {
                             #return_enum
                         }
                     )*
-                    panic!("Some version of the enum should be present!")
+                    Err(::clapme::ClapMeError::missing(orig_name))
+                }
+                fn from_clap_optional(_name: &str, _matches: &::clapme::clap::ArgMatches)
+                                      -> Result<Option<Self>, ::clapme::ClapMeError> {
+                    // Only when no variant at all is given is the
+                    // value `None`.
+                    match Self::from_clap(_name, _matches) {
+                        Ok(x) => Ok(Some(x)),
+                        Err(ref e) if e.kind == ::clapme::ClapMeErrorKind::MissingArgument
+                            && e.flag == _name => Ok(None),
+                        Err(e) => Err(e),
+                    }
                 }
                 fn requires_flags(_name: &str) -> Vec<String> {
                     // This is a little hokey, but we just list an
@@ -439,6 +1305,20 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
                     // achieve this, but I don't think it does.
                     Vec::new()
                 }
+                #[allow(unused_variables)]
+                fn to_clap_args(&self, _name: &str) -> Vec<::std::ffi::OsString> {
+                    #to_clap_args
+                }
+                fn to_subcommand_args(&self) -> Vec<::std::ffi::OsString> {
+                    match *self {
+                        #( #patterns2 => {
+                            let _prefix = String::new();
+                            let mut args = vec![::std::ffi::OsString::from(#subnames3)];
+                            args.extend(#sub_args);
+                            args
+                        } )*
+                    }
+                }
             };
             s
         },
@@ -450,15 +1330,75 @@ pub fn clapme(raw_input: proc_macro::TokenStream) -> proc_macro::TokenStream {
         <#(#generic_types: ::clapme::ClapMe),*>
     };
 
+    let about = about_tokens(&input.attrs);
+    let element = match input.data {
+        Struct(DataStruct { fields: syn::Fields::Unnamed(ref unnamed), .. })
+            if unnamed.unnamed.len() > 1 => quote!(),
+        Struct(_) => quote!(impl#bounds ::clapme::ClapMeElement for #name#generics {}),
+        Enum(ref e) if !attrs.subcommand
+            && e.variants.iter().all(|v| matches!(v.fields, syn::Fields::Unit)) =>
+        {
+            value_enum_impl(name, &bounds, generics, e, &attrs)
+        },
+        _ => quote!(),
+    };
     let tokens2: proc_macro2::TokenStream = quote!{
         impl#bounds ::clapme::ClapMe for #name#generics {
+            fn app<'a, 'b>() -> ::clapme::clap::App<'a, 'b> {
+                ::clapme::clap::App::new(env!("CARGO_PKG_NAME"))
+                    .version(env!("CARGO_PKG_VERSION"))
+                    .author(env!("CARGO_PKG_AUTHORS"))
+                    #about
+            }
             #myimpl
         }
+        #element
     };
     // println!("\n\n{}", tokens2);
     tokens2.into()
 }
 
+/// The implementation of `ClapMeValue` for an enum with only unit
+/// variants, which is given by the name of a variant (ignoring case),
+/// so that a `Vec` of it can be given as `--mode fast --mode safe`.
+fn value_enum_impl(name: &syn::Ident, bounds: &proc_macro2::TokenStream,
+                   generics: &syn::Generics, e: &syn::DataEnum,
+                   attrs: &Attrs) -> proc_macro2::TokenStream {
+    let idents: Vec<_> = e.variants.iter().map(|v| v.ident.clone()).collect();
+    let idents2 = idents.clone();
+    let values: Vec<_> = e.variants.iter()
+        .map(|v| variant_subcommand_name(v, &attrs.rename_all))
+        .collect();
+    let values2 = values.clone();
+    let lowers = values.iter().map(|v| v.to_lowercase());
+    let choices = values.join(", ");
+    let value_name = if attrs.value_enum {
+        values.join("|")
+    } else {
+        camel_case_to_kebab(&name.to_string()).to_uppercase()
+    };
+    let names = std::iter::repeat(name);
+    let names2 = std::iter::repeat(name);
+    quote!{
+        impl#bounds ::clapme::ClapMeValue for #name#generics {
+            fn value_name() -> &'static str {
+                #value_name
+            }
+            fn parse_value(value: &::std::ffi::OsStr) -> Result<Self, String> {
+                match value.to_str().map(|s| s.to_lowercase()) {
+                    #( Some(ref s) if s == #lowers => Ok(#names::#idents), )*
+                    _ => Err(format!("expected one of {}", #choices)),
+                }
+            }
+            fn to_value(&self) -> ::std::ffi::OsString {
+                match *self {
+                    #( #names2::#idents2 => ::std::ffi::OsString::from(#values2), )*
+                }
+            }
+        }
+    }
+}
+
 fn camel_case_to_kebab(name: &str) -> String {
     if name.chars().next() == Some('_') {
         "".to_string()
@@ -489,3 +1429,45 @@ fn snake_case_to_kebab(name: &str) -> String {
         name.to_string().replace("_", "-")
     }
 }
+
+/// Converts a field or variant name to the given `rename_all` style.
+fn rename_with(name: &str, style: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    for part in name.split('_').filter(|p| !p.is_empty()) {
+        let mut word = String::new();
+        let mut after_lower = false;
+        for c in part.chars() {
+            if after_lower && c.is_ascii_uppercase() {
+                words.push(word);
+                word = String::new();
+            }
+            after_lower = !c.is_ascii_uppercase();
+            word.push(c);
+        }
+        words.push(word);
+    }
+    let lower: Vec<String> = words.iter().map(|w| w.to_lowercase()).collect();
+    let capitalized: Vec<String> = lower.iter().map(|w| {
+        let mut cs = w.chars();
+        match cs.next() {
+            Some(c) => c.to_uppercase().chain(cs).collect(),
+            None => String::new(),
+        }
+    }).collect();
+    match style {
+        "kebab-case" => lower.join("-"),
+        "snake_case" => lower.join("_"),
+        "lowercase" => lower.concat(),
+        "UPPERCASE" => lower.concat().to_uppercase(),
+        "SCREAMING-KEBAB-CASE" => lower.join("-").to_uppercase(),
+        "SCREAMING_SNAKE_CASE" => lower.join("_").to_uppercase(),
+        "PascalCase" => capitalized.concat(),
+        "camelCase" => {
+            let mut out = lower.first().cloned().unwrap_or_default();
+            out.extend(capitalized.iter().skip(1).cloned());
+            out
+        },
+        "verbatim" => name.to_string(),
+        _ => panic!("Unknown rename_all style: {:?}", style),
+    }
+}
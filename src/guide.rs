@@ -88,7 +88,8 @@
 //! ```
 //! I would always documentation for actual projects, so I'll try
 //! to model that here, even though these examples are all
-//! fictitious.
+//! fictitious.  The first paragraph of a doc comment is shown
+//! with `-h`, and the entire comment with `--help`.
 //! ## How the flag is determined
 //! We saw above that the flag just had `--` prepended to the
 //! field name.  The rule in general is only slightly more
@@ -118,6 +119,37 @@
 //! Thus you can create most any flag name you care for, and it is
 //! easy to tell which flag corresponds to which field in your
 //! struct.
+//! ## Customizing flags
+//! When you need a flag name that does not follow from the field
+//! name, you can use a `#[clapme(...)]` attribute.  `long` sets
+//! the entire flag (ignoring any prefix from nesting), `rename`
+//! replaces just the field's part of the name, `short` adds a
+//! single-letter flag, and `alias` or `aliases` add hidden
+//! alternative names.  A `rename_all` attribute on the struct
+//! itself changes the case convention used for all its fields.
+//! ```ignore
+//! struct Custom {
+//!     /// Print excess messages.
+//!     #[clapme(short = 'v')]
+//!     verbose: bool,
+//!     /// The number of atoms.
+//!     #[clapme(short = 'N', long = "num-atoms", alias = "atoms")]
+//!     n: u32,
+//! }
+//! ```
+//! This gives the following usage.
+//! ```ignore
+//! custom 
+//! 
+//! USAGE:
+//!     custom [FLAGS] --num-atoms <INT>
+//! 
+//! FLAGS:
+//!     -v, --verbose    Print excess messages.
+//! 
+//! OPTIONS:
+//!     -N, --num-atoms <INT>    The number of atoms.
+//! ```
 //! ## Other types
 //! You can add most standard library types to your struct,
 //! basically anything that can be read or parsed from a `&str`.
@@ -155,7 +187,25 @@
 //! specify an expression such as `1/3` or `sqrt(2)`.  This is
 //! most useful for floating point input where makes it easier to
 //! give high-precision input when needed, but may also be helpful
-//! for integers.
+//! for integers.  A floating point value may also be `inf`,
+//! `-inf` or `NaN`.
+//! 
+//! The `std::num::NonZero*` types work just like the integers,
+//! but reject a value of zero.  You can also wrap any type in a
+//! `Box`, `Rc` or `Arc`, which parses just like the type inside.
+//! A recursive type such as a tree, holding an `Option<Box<Self>>`,
+//! is given flags such as `--child-child-value` down to three
+//! levels deep, or fewer if it has more than two recursive fields,
+//! since it would otherwise need an endless number of flags.
+//! 
+//! A `std::time::Duration` is given with units, as in `90s`,
+//! `5m30s`, `1.5h` or `250ms`, and a number with no unit is in
+//! seconds.  Each amount may again be an expression, as in
+//! `2*60s`.
+//! 
+//! A `clapme::ByteSize` holds a number of bytes, which may be
+//! given with SI units as in `10MB` or `512k`, or with binary units
+//! as in `4GiB`.
 //! ## Optional flags
 //! In the previous examples, every flag (except a `bool` flag)
 //! was required to be specified by the user.  If you want a flag
@@ -177,6 +227,71 @@
 //!         --name <STRING>    The name is an optional argument.
 //! ```
 //! The value is then `None` if the user did not specify that flag.
+//! An `Option<bool>` flag also accepts an explicit value, as in
+//! `--verbose=false`, so you can tell a flag that was explicitly
+//! turned off from one that was never given.
+//! ## Default values
+//! Often, rather than `None`, you would like a missing flag to
+//! take on a sensible default.  You can give the default as a
+//! rust expression with `#[clapme(default = ...)]`, or just say
+//! `#[clapme(default)]` to use the `Default` trait.
+//! ```ignore
+//! struct Defaults {
+//!     /// The temperature.
+//!     #[clapme(default = 300.0)]
+//!     temperature: f64,
+//!     /// The number of steps.
+//!     #[clapme(default)]
+//!     steps: u64,
+//! }
+//! ```
+//! This gives the following usage.
+//! ```ignore
+//! defaults 
+//! 
+//! USAGE:
+//!     defaults [OPTIONS]
+//! 
+//! OPTIONS:
+//!         --steps <INT>            The number of steps. [default: 0]
+//!         --temperature <FLOAT>    The temperature. [default: 300]
+//! ```
+//! The default is shown in the help as a user would type it.  You
+//! can also write the default just as a user would type it on the
+//! command line, with `#[clapme(default_value = "64MiB")]`, or
+//! `"0 1"` for a tuple, and an invalid default is reported just
+//! like an invalid value on the command line.
+//! A `bool` that is `true` by default also gets a `--no-` flag
+//! to turn it off.  You can give any `bool` such a flag with
+//! `#[clapme(negatable)]`, in which case whichever of the two
+//! flags comes last wins.
+//! ## Positional arguments
+//! An argument that is given without a flag, such as an input
+//! file, is marked with `#[clapme(positional)]`.  Positional
+//! arguments are given in the order of the fields, and a final
+//! `Vec` takes any remaining arguments, including those after a
+//! `--`.
+//! ```ignore
+//! struct Positional {
+//!     /// The program to run.
+//!     #[clapme(positional)]
+//!     program: String,
+//!     /// Arguments for the program.
+//!     #[clapme(positional)]
+//!     args: Vec<String>,
+//! }
+//! ```
+//! This gives the following usage.
+//! ```ignore
+//! positional 
+//! 
+//! USAGE:
+//!     positional <program> [args]...
+//! 
+//! ARGS:
+//!     <program>    The program to run.
+//!     <args>...    Arguments for the program.
+//! ```
 //! ## Exclusive flags
 //! If you want to make certain flags/values mutually exclusive,
 //! you use an `enum` (just as always, in rust).
@@ -189,7 +304,9 @@
 //!         a: String,
 //!         /// This is the "b" value, which you cannot specify unless
 //!         /// you also specify the "a" value.
-//!         /// Only one line of comment shows up in the help.
+//!         ///
+//!         /// Only the first paragraph of a comment shows up with
+//!         /// `-h`, while `--help` shows the whole comment.
 //!         b: String,
 //!     },
 //!     /// A string that cannot be used with any other flag
@@ -210,15 +327,18 @@
 //! 
 //! OPTIONS:
 //!         --first-a <STRING>        This is the "a" value
-//!         --first-b <STRING>        Only one line of comment shows up in the help.
+//!         --first-b <STRING>        This is the "b" value, which you cannot specify unless you also specify the "a" value.
 //!         --second-flag <STRING>    A string that cannot be used with any other flag
 //! ```
 //! This example illustrates the three kinds of `enum` variants.
 //! Sadly, the help message does not indicate that these flags are
 //! exlusive.  However, if a user tries to specify both `--third`
 //! and `--second FOO`, however, they will get a nice error
-//! message.  Note that you cannot use a tuple variant with more
-//! than one field.
+//! message.  A tuple variant with more than one field, such as
+//! `Size(f64, f64)`, takes one value for each field, as in `--size
+//! 2 3`, and the same goes for a field whose type is a tuple
+//! struct like `struct Range(f64, f64)`.  Each field must be a
+//! single value, such as a number or a string.
 //! Note that the rules for constructing flags from enum variants
 //! are more complicated than for struct fields.  This is because
 //! by convention variants are given `CamelCase` names, which
@@ -226,6 +346,70 @@
 //! underscore, then it is treated like a field name (as described
 //! above), with any trailing underscores removed.  Otherwise the
 //! name is converted from `CamelCase` to `kebab-case`.
+//! ## Choosing a value
+//! Often an enum just names one of a few choices, and you would
+//! rather have a single flag taking the name of the choice than
+//! a flag for each choice.  For this you can use
+//! `#[clapme(value_enum)]` on an enum with only unit variants.
+//! ```ignore
+//! #[derive(ClapMe)]
+//! #[clapme(value_enum)]
+//! enum Color {
+//!     /// The color of blood
+//!     Red,
+//!     Green,
+//!     /// The color of the sky
+//!     Blue,
+//! }
+//! #[derive(ClapMe)]
+//! struct Paint {
+//!     /// The color to paint
+//!     color: Color,
+//! }
+//! ```
+//! This gives the following usage.
+//! ```ignore
+//! paint 
+//! 
+//! USAGE:
+//!     paint --color <red|green|blue>
+//! 
+//! OPTIONS:
+//!         --color <red|green|blue>    The color to paint
+//! ```
+//! The user may write the value in any case, as in `--color Red`,
+//! and the doc comments of the variants are shown with `--help`.
+//! ## Subcommands
+//! If you would rather have your user choose between the variants
+//! of an `enum` using subcommands (like `git commit` or `cargo
+//! build`), you can add `#[clapme(subcommand)]` to the `enum`, or
+//! to a field whose type is the `enum`.  Each variant then becomes
+//! a subcommand with its own flags, and an `enum` within a variant
+//! gives nested subcommands.
+//! ```ignore
+//! #[clapme(subcommand)]
+//! enum Tool {
+//!     /// Build the project
+//!     Build {
+//!         /// Build with optimizations
+//!         release: bool,
+//!     },
+//!     /// Remove build products
+//!     Clean,
+//! }
+//! ```
+//! This gives the following usage.
+//! ```ignore
+//! tool 
+//! 
+//! USAGE:
+//!     tool <SUBCOMMAND>
+//! 
+//! SUBCOMMANDS:
+//!     build    Build the project
+//!     clean    Remove build products
+//! ```
+//! A user would then run, for instance, `tool build --release`.
 //! ## Nesting types
 //! You can use any `ClapMe` type as a field within a struct or
 //! enum.  Doing so will give flag names that combine the nested
@@ -306,7 +490,65 @@
 //! have not bothered to create an entire example.  I will list
 //! them here when they come to mind.
 //! 1. You can use a `Vec<T>` for many values of `T` to create an
-//! option that can be specified more than once.
+//! option that can be specified more than once.  A
+//! `VecDeque`, `HashSet` or `BTreeSet` works the same way, and
+//! `#[clapme(duplicates = "warn")]` on a set warns when the
+//! same value is given twice.
+//! With `#[clapme(delimiter = ',')]` a collection may also be
+//! given several values at once, as in `--ids 1,2,3`, and
+//! `min_values` and `max_values` limit how many values it may
+//! hold.
+//! A `Vec` of one of your own structs makes each of its flags
+//! repeatable.  The flags of each element are given together,
+//! and a flag given a second time begins the next element, so
+//! `--point-x 1 --point-y 2 --point-x 3 --point-y 4` gives two
+//! points.  A member that one element may be given more than
+//! once, such as a `Vec` or a `clapme::Count`, stays with the
+//! element it is given in.
+//! A `Vec` of an enum with only unit variants takes the names
+//! of the variants, as in `--mode fast --mode safe`.  An
+//! `Option` of any enum is `None` when none of its variants is
+//! given.
+//! 2. A `clapme::Count` field gives a flag that may be repeated,
+//! such as `-vvv` for a verbosity level, and holds the number
+//! of times it was given.
+//! 3. You can read a flag from an environment variable when it is
+//! not given on the command line, using `#[clapme(env =
+//! "NAME")]` on a field, or `#[clapme(env_prefix = "MYAPP")]`
+//! on a struct so that `--sys-well-width` may be given as
+//! `MYAPP_SYS_WELL_WIDTH`.
+//! 4. The doc comment on your type becomes the description of your
+//! program, and its version and author are taken from your
+//! `Cargo.toml`, so that `--version` works as expected.  Use
+//! `from_args_named` if the program should be named after the
+//! binary that was run rather than after the package.
+//! 5. You can turn a value back into command-line arguments with
+//! `to_args`, so that `T::from_iter(x.to_args())` gives back
+//! `x`.  This is handy for running a child process with a
+//! modified copy of your options.
+//! 6. You can save your parameters to a file with `save_params`
+//! and read them back with `load_params`.  The file holds one
+//! argument per line, such as `--temperature=300`, so it is
+//! easy to edit.  If you parse your arguments with
+//! `from_args_with_params`, your user may give `--load-params
+//! FILE` and `--save-params FILE`.  A flag given on the command
+//! line (in any of its forms) replaces that flag in the file,
+//! and so do positional arguments and a subcommand's flags.
+//! 7. For a parameter sweep, `sweep_from_iter` and
+//! `sweep_from_args` allow any numeric flag to be given a list
+//! of values such as `1,2,3` or a range such as `0:10:0.5`, and
+//! return a `Vec` holding every combination of those values.
+//! 
+//! 8. A `HashMap` or `BTreeMap` gives a flag that may be repeated
+//! as in `--define alpha=1 --define beta=2`.  A key given twice
+//! takes its last value, unless you ask for an error with
+//! `#[clapme(duplicates = "error")]`.
+//! 
+//! 9. A tuple such as `(f64, f64)` or an array such as `[f64; 3]`
+//! takes one value for each element, as in `--origin 0 0 1`,
+//! and an error says which position was wrong.  With
+//! `#[clapme(delimiter = ',')]` it may also be given as
+//! `--origin=0,0,1`.
 //! ## Conclusion
 //! There is more that could be said and more possible examples,
 //! but I think this is enough to get you started using `ClapMe`.
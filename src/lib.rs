@@ -11,6 +11,9 @@
 //! This crate defines the `ClapMe` trait and its custom derive.
 //!
 //! To learn to use clapme, you should read [the clapme guide](guide/index.html).
+//!
+//! clapme requires Rust 1.51 or later, since it implements `ClapMe`
+//! for arrays `[T; N]` of any length.
 
 extern crate clap as _clap;
 extern crate meval;
@@ -24,6 +27,10 @@ pub use clapme_derive::*;
 
 use std::str::FromStr;
 use std::ffi::OsString;
+use std::fmt;
+use std::collections::{HashMap, HashSet, BTreeMap, BTreeSet, VecDeque};
+use std::rc::Rc;
+use std::sync::Arc;
 
 pub mod guide;
 
@@ -49,6 +56,41 @@ pub struct ArgInfo<'a> {
     pub multiple: bool,
     /// Help string (if any)
     pub help: &'a str,
+    /// Longer help string shown with `--help` (if any)
+    pub long_help: &'a str,
+    /// A single-character short flag, if any.
+    pub short: Option<char>,
+    /// Additional hidden long flags for this argument.
+    pub aliases: &'a [&'a str],
+    /// An environment variable to read the value from when the flag
+    /// is not given.
+    pub env: Option<&'a str>,
+    /// A prefix for environment variables to be read for every flag
+    /// within a struct.
+    pub env_prefix: Option<&'a str>,
+    /// Should an enum be parsed as a set of subcommands rather than
+    /// as mutually exclusive flags?
+    pub subcommand: bool,
+    /// Is this a positional argument, even though it has a name?
+    pub positional: bool,
+    /// Does a `bool` flag also get a `--no-` flag to turn it off?
+    pub negatable: bool,
+    /// Is this argument an `Option`, so that a `bool` flag should
+    /// accept an explicit value, as in `--foo=false`?
+    pub optional: bool,
+    /// The delimiter between several values given at once to a
+    /// collection, as in `--ids 1,2,3`.
+    pub delimiter: Option<&'a str>,
+    /// The fewest values a collection may be given.
+    pub min_values: Option<u64>,
+    /// The most values a collection may be given.
+    pub max_values: Option<u64>,
+    /// Collects the names of numeric flags, which can be swept over by
+    /// `sweep_from_iter`.
+    pub sweep_flags: Option<&'a std::cell::RefCell<Vec<String>>>,
+    /// Collects how each flag is given, so that a parameters file can
+    /// be merged with the command line.
+    pub param_flags: Option<&'a std::cell::RefCell<ParamFlags>>,
 }
 
 impl<'a> ArgInfo<'a> {
@@ -60,10 +102,545 @@ impl<'a> ArgInfo<'a> {
             required_flags: &[],
             multiple: false,
             help: "",
+            long_help: "",
             conflicted_flags: Vec::new(),
             required_unless_one: Vec::new(),
+            short: None,
+            aliases: &[],
+            env: None,
+            env_prefix: None,
+            subcommand: false,
+            positional: false,
+            negatable: false,
+            optional: false,
+            delimiter: None,
+            min_values: None,
+            max_values: None,
+            sweep_flags: None,
+            param_flags: None,
+        }
+    }
+
+    /// Adds `arg` to `app` and passes the result to `f`.  If there is
+    /// an environment variable for this argument, a hidden argument
+    /// holding its value is added as well, which `from_clap` falls
+    /// back on when the flag itself is not given.
+    pub fn with_arg<T>(&self, app: clap::App, arg: clap::Arg,
+                       f: impl FnOnce(clap::App) -> T) -> T {
+        match self.env {
+            None => f(app.arg(arg)),
+            Some(env) => {
+                let fallback = env_fallback(self.name);
+                let arg = if arg.is_set(clap::ArgSettings::Required) {
+                    arg.required_unless(&fallback)
+                } else {
+                    arg
+                };
+                f(app.arg(arg)
+                  .arg(clap::Arg::with_name(&fallback).long(&fallback)
+                       .hidden(true)
+                       .takes_value(true)
+                       .env(env)))
+            }
+        }
+    }
+
+    /// Notes that this argument holds a number, which can be swept
+    /// over.
+    pub fn sweepable(&self) {
+        if let Some(flags) = self.sweep_flags {
+            flags.borrow_mut().push(self.name.to_string());
+        }
+    }
+
+    /// Notes that this argument takes `values` values after its flag
+    /// (or any number if `None`), for merging a parameters file with
+    /// the command line.
+    fn takes_values(&self, values: Option<usize>) {
+        if let Some(flags) = self.param_flags {
+            flags.borrow_mut().values.insert(self.name.to_string(), values);
+        }
+    }
+
+    /// Notes that this argument may be given more than once within a
+    /// single value, so that a repeated flag does not begin a new
+    /// element of a `Vec` of structs.
+    fn repeatable(&self) {
+        if let Some(flags) = self.param_flags {
+            flags.borrow_mut().repeated.insert(self.name.to_string());
+        }
+    }
+
+    /// Notes the name of a subcommand, for merging a parameters file
+    /// with the command line.
+    pub fn subcommand_named(&self, name: &str) {
+        if let Some(flags) = self.param_flags {
+            flags.borrow_mut().subcommands.insert(name.to_string());
+        }
+    }
+
+    /// A `clap::Arg` for a flag that takes no value, with its name,
+    /// help and relationships to other flags filled in.  An empty
+    /// name gives a positional argument, as does `self.positional`.
+    pub fn arg<'x>(&'x self) -> clap::Arg<'x, 'x> {
+        let conflicts: Vec<&str> = self.conflicted_flags.iter().map(AsRef::as_ref).collect();
+        let mut arg = clap::Arg::with_name(self.name)
+            .requires_all(self.required_flags)
+            .conflicts_with_all(&conflicts)
+            .help(self.help);
+        if !self.long_help.is_empty() {
+            arg = arg.long_help(self.long_help);
+        }
+        if !self.name.is_empty() && !self.positional {
+            arg = arg.long(self.name).aliases(self.aliases);
+            if let Some(c) = self.short {
+                arg = arg.short(c.to_string());
+            }
+            if let Some(flags) = self.param_flags {
+                let mut flags = flags.borrow_mut();
+                flags.add_flag(self.name, self.short, self.aliases);
+                flags.conflicts.insert(self.name.to_string(), self.conflicted_flags.clone());
+            }
+        }
+        arg
+    }
+
+    /// A `clap::Arg` for an argument that takes a value, which is
+    /// required unless `self.required` is false.
+    pub fn value_arg<'x>(&'x self, value_name: &'x str) -> clap::Arg<'x, 'x> {
+        let ruo: Vec<&str> = self.required_unless_one.iter().map(AsRef::as_ref).collect();
+        let mut arg = self.arg()
+            .takes_value(true)
+            .required(self.required);
+        if !self.positional {
+            arg = arg.value_name(value_name);
+        }
+        if self.multiple {
+            arg = arg.multiple(true).number_of_values(1);
+            self.repeatable();
+        }
+        self.takes_values(Some(1));
+        if ruo.is_empty() {
+            arg
+        } else {
+            arg.required_unless_one(&ruo)
+        }
+    }
+
+    /// A `clap::Arg` for a tuple, which takes one value for each of
+    /// `value_names`, which must not be empty.  The values may start
+    /// with a `-`, so that `--origin -1 0` works.
+    pub fn tuple_arg<'x>(&'x self, value_names: &'x [&'x str]) -> clap::Arg<'x, 'x> {
+        let mut arg = self.value_arg(value_names[0])
+            .allow_hyphen_values(true)
+            .number_of_values(value_names.len() as u64);
+        if !self.positional {
+            // value_arg already named the first value
+            arg = arg.value_names(&value_names[1..]);
+        }
+        if let Some(d) = self.delimiter {
+            arg = arg.use_delimiter(true).value_delimiter(d);
+        }
+        self.takes_values(Some(value_names.len()));
+        arg
+    }
+
+    /// A `clap::Arg` for a collection, which takes any number of
+    /// values unless limited by `self.min_values` and
+    /// `self.max_values`.  It is only required if it needs at least
+    /// one value, since clap checks `min_values` only when the flag
+    /// is given.
+    pub fn values_arg<'x>(&'x self, value_name: &'x str) -> clap::Arg<'x, 'x> {
+        let needs_values = self.min_values.map_or(false, |n| n > 0);
+        let mut arg = self.value_arg(value_name)
+            .required(self.required && needs_values)
+            .multiple(true);
+        if let Some(d) = self.delimiter {
+            arg = arg.use_delimiter(true).value_delimiter(d);
+        }
+        if let Some(n) = self.min_values {
+            arg = arg.min_values(n);
+        }
+        if let Some(n) = self.max_values {
+            arg = arg.max_values(n);
+        }
+        self.takes_values(if self.multiple { Some(1) } else { None });
+        self.repeatable();
+        arg
+    }
+}
+
+/// The kind of problem described by a `ClapMeError`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClapMeErrorKind {
+    /// clap rejected the arguments, or displayed help or version
+    /// information.
+    Clap(clap::ErrorKind),
+    /// A value was given that could not be parsed.
+    InvalidValue,
+    /// A required argument was not given.
+    MissingArgument,
+}
+
+/// An error encountered while parsing arguments.
+#[derive(Debug)]
+pub struct ClapMeError {
+    /// What went wrong.
+    pub kind: ClapMeErrorKind,
+    /// The name of the argument, which is empty for a positional
+    /// argument or an error reported by clap.
+    pub flag: String,
+    /// The value given for the argument, if any.
+    pub value: Option<String>,
+    /// The underlying error, if any.
+    pub error: Option<Box<dyn std::error::Error + Send + Sync>>,
+}
+
+impl ClapMeError {
+    /// An error for a required argument that was not given.
+    pub fn missing(flag: &str) -> Self {
+        ClapMeError {
+            kind: ClapMeErrorKind::MissingArgument,
+            flag: flag.to_string(),
+            value: None,
+            error: None,
+        }
+    }
+    /// An error for a value that could not be parsed.
+    pub fn invalid_value<E>(flag: &str, value: &str, error: E) -> Self
+        where E: Into<Box<dyn std::error::Error + Send + Sync>>
+    {
+        ClapMeError {
+            kind: ClapMeErrorKind::InvalidValue,
+            flag: flag.to_string(),
+            value: Some(value.to_string()),
+            error: Some(error.into()),
+        }
+    }
+    /// Prints the error and exits the process.  Help and version
+    /// requests go to stdout with a zero exit code, just as clap does
+    /// it, while genuine errors go to stderr with exit code 1.
+    pub fn exit(&self) -> ! {
+        if let Some(e) = self.error.as_ref().and_then(|e| e.downcast_ref::<clap::Error>()) {
+            e.exit()
+        }
+        eprintln!("error: {}", self);
+        std::process::exit(1)
+    }
+}
+
+impl fmt::Display for ClapMeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let flag = if self.flag.is_empty() {
+            "argument".to_string()
+        } else {
+            format!("'--{}'", self.flag)
+        };
+        match self.kind {
+            ClapMeErrorKind::Clap(_) => match self.error {
+                Some(ref e) => write!(f, "{}", e),
+                None => write!(f, "clap error"),
+            },
+            ClapMeErrorKind::InvalidValue => {
+                write!(f, "Invalid value for {}", flag)?;
+                if let Some(ref v) = self.value {
+                    write!(f, " {:?}", v)?;
+                }
+                if let Some(ref e) = self.error {
+                    write!(f, ": {}", e)?;
+                }
+                Ok(())
+            },
+            ClapMeErrorKind::MissingArgument => {
+                write!(f, "The required {} was not provided", flag)?;
+                if let Some(ref e) = self.error {
+                    write!(f, " {}", e)?;
+                }
+                Ok(())
+            },
+        }
+    }
+}
+
+impl std::error::Error for ClapMeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self.error {
+            Some(ref e) => Some(&**e),
+            None => None,
+        }
+    }
+}
+
+impl From<clap::Error> for ClapMeError {
+    fn from(e: clap::Error) -> Self {
+        ClapMeError {
+            kind: ClapMeErrorKind::Clap(e.kind),
+            flag: String::new(),
+            value: None,
+            error: Some(Box::new(e)),
+        }
+    }
+}
+
+/// The name of the hidden argument holding the value of the
+/// environment variable for `name`.  It contains a `=` so it can never
+/// be given as a flag.
+fn env_fallback(name: &str) -> String {
+    format!("{}=env", name)
+}
+
+/// The name to look up the value of `name` in `matches`, which is the
+/// environment variable fallback if the flag itself was not given.
+fn lookup_name(matches: &clap::ArgMatches, name: &str) -> String {
+    if matches.is_present(name) {
+        name.to_string()
+    } else {
+        env_fallback(name)
+    }
+}
+
+fn str_to_bool(s: &str) -> Result<bool, String> {
+    match s.to_lowercase().as_ref() {
+        "true" | "yes" | "on" | "1" => Ok(true),
+        "false" | "no" | "off" | "0" | "" => Ok(false),
+        _ => Err(format!("expected true or false, found {:?}", s)),
+    }
+}
+
+/// The arguments giving `value` for the argument `name`, which is
+/// positional if `name` is empty.
+fn value_args(name: &str, value: &std::ffi::OsStr) -> Vec<OsString> {
+    if name.is_empty() {
+        vec![value.to_os_string()]
+    } else {
+        let mut arg = OsString::from(format!("--{}=", name));
+        arg.push(value);
+        vec![arg]
+    }
+}
+
+/// A type that is given on the command line as a single value, such
+/// as a number or a path.  Collections such as maps are built out of
+/// these.
+pub trait ClapMeValue: Sized {
+    /// The name of the value in the help, such as `INT`.
+    fn value_name() -> &'static str;
+    /// Parses a value given on the command line.
+    fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String>;
+    /// The command-line text giving this value.
+    fn to_value(&self) -> OsString;
+}
+
+/// A type that can be held in a `Vec` or `VecDeque`.  Every
+/// `ClapMeValue` is one, taking a repeated flag with one value for
+/// each element.  A derived struct is one too, in which case each of
+/// its flags may be repeated, and the elements are formed by matching
+/// up the values in the order they are given, so that
+/// `--point-x 1 --point-y 2 --point-x 3 --point-y 4` gives two
+/// points.
+pub trait ClapMeElement: ClapMe {
+    /// Sets up the arguments for many values of this type.
+    fn with_clap_many<T>(mut info: ArgInfo, app: clap::App,
+                         f: impl FnOnce(clap::App) -> T) -> T {
+        info.required = false;
+        info.multiple = true;
+        Self::with_clap(info, app, f)
+    }
+    /// Parses the clap info to obtain many values.
+    fn from_clap_many(name: &str, matches: &clap::ArgMatches) -> Result<Vec<Self>, ClapMeError> {
+        from_clap_group(name, matches)
+    }
+    /// The command-line arguments that give this value as one of many.
+    fn to_clap_args_many(&self, name: &str) -> Vec<OsString> {
+        group_element_args::<Self>(name, self.to_clap_args(name))
+    }
+}
+
+impl<T: ClapMeValue + ClapMe> ClapMeElement for T {
+    fn with_clap_many<TT>(info: ArgInfo, app: clap::App,
+                          f: impl FnOnce(clap::App) -> TT) -> TT {
+        info.with_arg(app, info.values_arg(T::value_name())
+                           .validator_os(|s| T::parse_value(s).map(|_| ())
+                                         .map_err(|e| OsString::from(format!("{:?}: {}", s, e)))),
+                      f)
+    }
+    fn from_clap_many(name: &str, matches: &clap::ArgMatches) -> Result<Vec<Self>, ClapMeError> {
+        parse_values(name, matches)
+    }
+    fn to_clap_args_many(&self, name: &str) -> Vec<OsString> {
+        value_args(name, &self.to_value())
+    }
+}
+
+/// Parses the value of the argument `name`, for an implementation
+/// of `ClapMe` by way of `ClapMeValue`.
+#[doc(hidden)]
+pub fn value_from_clap<T: ClapMeValue>(name: &str, matches: &clap::ArgMatches)
+                                       -> Result<T, ClapMeError> {
+    match matches.value_of_os(lookup_name(matches, name)) {
+        Some(s) => T::parse_value(s)
+            .map_err(|e| ClapMeError::invalid_value(name, &s.to_string_lossy(), e)),
+        None => Err(ClapMeError::missing(name)),
+    }
+}
+
+/// The arguments giving `value` for the argument `name`, for an
+/// implementation of `ClapMe` by way of `ClapMeValue`.
+#[doc(hidden)]
+pub fn value_to_clap_args<T: ClapMeValue>(value: &T, name: &str) -> Vec<OsString> {
+    value_args(name, &value.to_value())
+}
+
+/// The values given for a tuple argument `name`, which must number
+/// exactly `num`.  A value from an environment variable is split at
+/// whitespace.
+#[doc(hidden)]
+pub fn tuple_values<'m>(name: &str, matches: &'m clap::ArgMatches, num: usize)
+                        -> Result<Vec<&'m std::ffi::OsStr>, ClapMeError> {
+    let lookup = lookup_name(matches, name);
+    let values: Vec<&std::ffi::OsStr> = match matches.values_of_os(&lookup) {
+        Some(v) => v.collect(),
+        None => return Err(ClapMeError::missing(name)),
+    };
+    let values = if lookup != name && values.len() == 1 && num > 1 {
+        match values[0].to_str() {
+            Some(s) => s.split_whitespace().map(|v| v.as_ref()).collect(),
+            None => values,
+        }
+    } else {
+        values
+    };
+    if values.len() != num {
+        let given: Vec<_> = values.iter().map(|v| v.to_string_lossy()).collect();
+        return Err(ClapMeError::invalid_value(name, &given.join(" "),
+                                              format!("expected {} values, found {}",
+                                                      num, values.len())));
+    }
+    Ok(values)
+}
+
+/// Parses the value in position `i` of a tuple argument `name`.
+#[doc(hidden)]
+pub fn parse_position<T: ClapMeValue>(name: &str, values: &[&std::ffi::OsStr], i: usize)
+                                      -> Result<T, ClapMeError> {
+    T::parse_value(values[i]).map_err(|e| {
+        ClapMeError::invalid_value(name, &values[i].to_string_lossy(),
+                                   format!("in position {}: {}", i + 1, e))
+    })
+}
+
+/// The arguments giving the values of a tuple for the argument
+/// `name`, which is positional if `name` is empty.
+#[doc(hidden)]
+pub fn tuple_to_args(name: &str, values: Vec<OsString>) -> Vec<OsString> {
+    if name.is_empty() {
+        values
+    } else {
+        let mut args = vec![OsString::from(format!("--{}", name))];
+        args.extend(values);
+        args
+    }
+}
+
+/// Parses the repeated flags of a group such as a struct into a value
+/// for each time they are given.  The flags of one element are given
+/// together, so a flag that is given a second time begins the next
+/// element, unless one element may be given that flag more than once,
+/// as for a collection.  Each element is then parsed on its own from
+/// just its own flags.
+fn from_clap_group<T: ClapMe>(name: &str, matches: &clap::ArgMatches) -> Result<Vec<T>, ClapMeError> {
+    let group_error = |mut e: ClapMeError, i: usize| {
+        e.error = Some(if name.is_empty() {
+            format!("for group {}", i + 1).into()
+        } else {
+            format!("for group {} of '--{}'", i + 1, name).into()
+        });
+        e
+    };
+    // Each time a flag was given, with its position and its values.
+    let mut given: Vec<(usize, String, Vec<OsString>)> = Vec::new();
+    for member in T::arg_names(name) {
+        let indices: Vec<usize> = match matches.indices_of(&member) {
+            Some(i) => i.collect(),
+            None => continue,
+        };
+        let values: Vec<OsString> = matches.values_of_os(&member)
+            .map(|v| v.map(|s| s.to_os_string()).collect())
+            .unwrap_or_default();
+        if values.is_empty() {
+            given.extend(indices.into_iter().map(|i| (i, member.clone(), Vec::new())));
+            continue;
+        }
+        let occurs = std::cmp::max(matches.occurrences_of(&member) as usize, 1);
+        if values.len() % occurs != 0 || indices.len() != values.len() {
+            let given: Vec<_> = values.iter().map(|v| v.to_string_lossy()).collect();
+            return Err(ClapMeError::invalid_value(&member, &given.join(" "),
+                                                  "cannot tell which group each value is for"));
+        }
+        let per = values.len() / occurs;
+        for (i, v) in indices.chunks(per).zip(values.chunks(per)) {
+            given.push((i[0], member.clone(), v.to_vec()));
         }
     }
+    if given.is_empty() {
+        return Ok(Vec::new());
+    }
+    given.sort_by_key(|g| g.0);
+
+    let repeatable = ParamFlags::of::<T>(name).repeated;
+    let mut elements: Vec<Vec<(String, Vec<OsString>)>> = Vec::new();
+    for (_, member, values) in given {
+        let repeated = match elements.last() {
+            Some(e) => !repeatable.contains(&member) && e.iter().any(|&(ref m, _)| *m == member),
+            None => true,
+        };
+        if repeated {
+            elements.push(Vec::new());
+        }
+        elements.last_mut().unwrap().push((member, values));
+    }
+
+    elements.into_iter().enumerate().map(|(i, element)| {
+        for f in T::requires_flags(name) {
+            if !element.iter().any(|&(ref m, _)| *m == f) {
+                return Err(group_error(ClapMeError::missing(&f), i));
+            }
+        }
+        let mut args = vec![OsString::new()];
+        for (member, values) in element {
+            if values.len() == 1 {
+                let mut arg = OsString::from(format!("--{}=", member));
+                arg.push(&values[0]);
+                args.push(arg);
+            } else {
+                args.push(OsString::from(format!("--{}", member)));
+                args.extend(values);
+            }
+        }
+        T::with_clap(ArgInfo::new(name), clap::App::new(""), |app| {
+            let matches = app.get_matches_from_safe(args)?;
+            T::from_clap(name, &matches)
+        }).map_err(|e| {
+            if e.kind == ClapMeErrorKind::MissingArgument { group_error(e, i) } else { e }
+        })
+    }).collect()
+}
+
+/// The arguments `args` giving one element of a group such as a
+/// struct, reordered so that the flags every element is given come
+/// first.  An optional flag then always follows the flags of its own
+/// element, so `from_clap_group` puts it back in the same element.
+fn group_element_args<T: ClapMe>(name: &str, args: Vec<OsString>) -> Vec<OsString> {
+    let required = T::requires_flags(name);
+    let mut flags = ParsedArgs::new(args, &ParamFlags::of::<T>(name)).flags;
+    flags.sort_by_key(|&(ref flag, _)| !required.contains(flag));
+    flags.into_iter().flat_map(|(_, given)| given).collect()
+}
+
+/// The text of a value, which must be unicode unless the type is
+/// read from an `OsStr`.
+fn os_to_str(value: &std::ffi::OsStr) -> Result<&str, String> {
+    value.to_str().ok_or_else(|| format!("invalid unicode in {:?}", value))
 }
 
 /// Any type of trait `ClapMe` can be used as an argument value.
@@ -74,16 +651,72 @@ pub trait ClapMe : Sized {
                     f: impl FnOnce(clap::App) -> T) -> T {
         f(app)
     }
-    /// Parses the clap info to obtain a value.  `None` is returned if
-    /// the argument was not required, and was also not provided.
-    fn from_clap(_name: &str, _app: &clap::ArgMatches) -> Option<Self> {
-        None
+    /// Parses the clap info to obtain a value.  An error of kind
+    /// `ClapMeErrorKind::MissingArgument` is returned if the argument
+    /// was not provided.
+    fn from_clap(name: &str, _app: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+        Err(ClapMeError::missing(name))
+    }
+    /// Parses the clap info to obtain a value if the argument was
+    /// provided, or `None` if it was not.
+    fn from_clap_optional(name: &str, matches: &clap::ArgMatches)
+                          -> Result<Option<Self>, ClapMeError> {
+        match Self::from_clap(name, matches) {
+            Ok(v) => Ok(Some(v)),
+            Err(ref e) if e.kind == ClapMeErrorKind::MissingArgument => Ok(None),
+            Err(e) => Err(e),
+        }
     }
     /// Parses the clap info to obtain a value.  `None` is returned if
     /// the argument was not required, and was also not provided.
     fn requires_flags(name: &str) -> Vec<String> {
         vec![name.to_string()]
     }
+    /// The names of the clap arguments for the argument `name`, which
+    /// are used to find the flags of each element of a `Vec` of
+    /// structs.
+    fn arg_names(name: &str) -> Vec<String> {
+        vec![name.to_string()]
+    }
+    /// The command-line arguments that would give this value for the
+    /// argument `name`.
+    fn to_clap_args(&self, _name: &str) -> Vec<OsString> {
+        Vec::new()
+    }
+    /// A value given more than once for the argument `name`, if any,
+    /// for collections such as maps where a later value replaces an
+    /// earlier one.
+    fn find_duplicate(_name: &str, _matches: &clap::ArgMatches) -> Option<String> {
+        None
+    }
+    /// The command-line arguments that would give `Some` of this
+    /// value for an `Option` argument `name`.
+    fn to_clap_args_optional(&self, name: &str) -> Vec<OsString> {
+        self.to_clap_args(name)
+    }
+    /// This value as it is shown in the help, such as for a default.
+    /// This is the command-line arguments that would give it, without
+    /// a flag.
+    fn to_help_value(&self) -> String {
+        let args: Vec<_> = self.to_clap_args("").iter()
+            .map(|a| a.to_string_lossy().into_owned())
+            .collect();
+        args.join(" ")
+    }
+    /// The command-line arguments for this value when it is used as
+    /// a subcommand.  This only differs from `to_clap_args` for an
+    /// `enum`.
+    fn to_subcommand_args(&self) -> Vec<OsString> {
+        self.to_clap_args("")
+    }
+    /// The command-line arguments that would give this value, so that
+    /// `T::from_iter(x.to_args())` gives back `x`.  As with
+    /// `std::env::args_os`, the first element is the program name.
+    fn to_args(&self) -> Vec<OsString> {
+        let mut args: Vec<OsString> = std::env::args_os().take(1).collect();
+        args.extend(self.to_clap_args(""));
+        args
+    }
     /// The help message for this struct.  This is most useful for
     /// test cases.
     fn help_message(cmdname: &str) -> String {
@@ -96,46 +729,683 @@ pub trait ClapMe : Sized {
                         })
     }
 
-    /// Parse command line arguments.
+    /// The `clap::App` used when parsing the command line.  The
+    /// derived implementation takes its name, version and author from
+    /// the cargo package, and its description from the doc comment.
+    fn app<'a, 'b>() -> clap::App<'a, 'b> {
+        clap::App::new("foo")
+    }
+
+    /// Parse command line arguments.  On error, the message is
+    /// printed and the process exits.
     fn from_args() -> Self {
-        Self::with_clap(ArgInfo::new(""),
-                        clap::App::new("foo"),
+        match Self::from_iter(std::env::args_os()) {
+            Ok(v) => v,
+            Err(e) => e.exit(),
+        }
+    }
+
+    /// Parse command line arguments, naming the program after the
+    /// binary as it was run rather than after the cargo package.
+    fn from_args_named() -> Self {
+        let name = std::env::args_os().next()
+            .and_then(|a| std::path::Path::new(&a).file_name()
+                      .map(|n| n.to_string_lossy().into_owned()));
+        let app = match name {
+            Some(name) => Self::app().name(name),
+            None => Self::app(),
+        };
+        match parse_with_app(app, std::env::args_os()) {
+            Ok(v) => v,
+            Err(e) => e.exit(),
+        }
+    }
+
+    /// Parse arguments given through an iterable thing such as a `Vec` or a slice.
+    fn from_iter<I,T>(args: I) -> Result<Self, ClapMeError>
+        where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        parse_with_app(Self::app(), args)
+    }
+
+    /// Parse arguments like `from_iter`, except that any numeric flag
+    /// may be given a list of values such as `1,2,3`, or a range such
+    /// as `0:10:0.5`.  Every combination of the values given is
+    /// returned.
+    fn sweep_from_iter<I,T>(args: I) -> Result<Vec<Self>, ClapMeError>
+        where
+        I: IntoIterator<Item = T>,
+        T: Into<OsString> + Clone,
+    {
+        let args: Vec<OsString> = args.into_iter().map(Into::into).collect();
+        let sweep_flags = std::cell::RefCell::new(Vec::new());
+        let param_flags = std::cell::RefCell::new(ParamFlags::default());
+        let mut info = ArgInfo::new("");
+        info.sweep_flags = Some(&sweep_flags);
+        info.param_flags = Some(&param_flags);
+        Self::with_clap(info, Self::app(),
                         |a| {
-                            let matches = a.get_matches();
-                            Self::from_clap("", &matches).unwrap()
+                            sweep_args(args, &sweep_flags.borrow(), &param_flags.borrow())?
+                                .into_iter().map(|argv| {
+                                let matches = a.clone().get_matches_from_safe(argv)?;
+                                Self::from_clap("", &matches)
+                            }).collect()
                         })
     }
 
-    /// Parse arguments given through an iterable thing such as a `Vec` or a slice.
-    fn from_iter<I,T>(args: I) -> Result<Self, clap::Error>
+    /// Parse command line arguments as a sweep, like
+    /// `sweep_from_iter`.  On error, the message is printed and the
+    /// process exits.
+    fn sweep_from_args() -> Vec<Self> {
+        match Self::sweep_from_iter(std::env::args_os()) {
+            Ok(v) => v,
+            Err(e) => e.exit(),
+        }
+    }
+
+    /// Writes this value to a parameters file, with one command-line
+    /// argument per line, such as `--temperature=300`.  The file may
+    /// be edited by hand, and read back with `load_params` or with
+    /// the `--load-params` flag of `from_args_with_params`.
+    fn save_params<P: AsRef<std::path::Path>>(&self, path: P) -> std::io::Result<()> {
+        use std::io::Write;
+        let mut f = std::fs::File::create(path)?;
+        for arg in self.to_clap_args("") {
+            writeln!(f, "{}", arg.to_string_lossy())?;
+        }
+        Ok(())
+    }
+
+    /// Reads a value from a parameters file, as written by
+    /// `save_params`.  Blank lines and lines starting with `#` are
+    /// ignored.
+    fn load_params<P: AsRef<std::path::Path>>(path: P) -> Result<Self, ClapMeError> {
+        let mut args = vec![OsString::new()];
+        args.extend(read_params(path.as_ref())?);
+        Self::from_iter(args)
+    }
+
+    /// Parse command line arguments like `from_args`, with two extra
+    /// flags: `--load-params FILE` reads parameters from a file, which
+    /// are overridden by any flags given on the command line, and
+    /// `--save-params FILE` writes the resulting parameters to a file.
+    fn from_args_with_params() -> Self {
+        match Self::from_iter_with_params(std::env::args_os()) {
+            Ok(v) => v,
+            Err(e) => e.exit(),
+        }
+    }
+
+    /// Parse arguments like `from_iter`, with the `--load-params` and
+    /// `--save-params` flags of `from_args_with_params`.
+    fn from_iter_with_params<I,T>(args: I) -> Result<Self, ClapMeError>
         where
         I: IntoIterator<Item = T>,
         T: Into<OsString> + Clone,
     {
-        Self::with_clap(ArgInfo::new(""),
-                        clap::App::new("foo"),
+        let mut param_flags = ParamFlags::of::<Self>("");
+        for name in ["load-params", "save-params"].iter() {
+            param_flags.add_flag(name, None, &[]);
+            param_flags.values.insert(name.to_string(), Some(1));
+        }
+        let args = merge_params(args.into_iter().map(Into::into).collect(), &param_flags)?;
+        let app = Self::app()
+            .arg(clap::Arg::with_name("load-params").long("load-params")
+                 .takes_value(true)
+                 .value_name("FILE")
+                 .help("Read parameters from FILE"))
+            .arg(clap::Arg::with_name("save-params").long("save-params")
+                 .takes_value(true)
+                 .value_name("FILE")
+                 .help("Save parameters to FILE"));
+        Self::with_clap(ArgInfo::new(""), app,
                         |a| {
                             let matches = a.get_matches_from_safe(args)?;
-                            Ok(Self::from_clap("", &matches).unwrap())
+                            let value = Self::from_clap("", &matches)?;
+                            if let Some(path) = matches.value_of_os("save-params") {
+                                value.save_params(path).map_err(|e| {
+                                    ClapMeError::invalid_value("save-params",
+                                                               &path.to_string_lossy(), e)
+                                })?;
+                            }
+                            Ok(value)
                         })
     }
 }
 
+/// Splits a sweep such as `1,2,5:10:2` into its values, which may
+/// each be an expression, or a range `start:stop` or
+/// `start:stop:step` that includes its end points.
+fn sweep_values(s: &str) -> Result<Vec<String>, String> {
+    let mut elements = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                elements.push(&s[start..i]);
+                start = i + 1;
+            },
+            _ => (),
+        }
+    }
+    elements.push(&s[start..]);
+    let mut values = Vec::new();
+    for e in elements {
+        let e = e.trim();
+        if !e.contains(':') {
+            str_to_f64(e)?;
+            values.push(e.to_string());
+            continue;
+        }
+        let parts = e.split(':').map(str_to_f64).collect::<Result<Vec<_>, _>>()?;
+        let (first, last, step) = match parts.len() {
+            2 => (parts[0], parts[1], 1.0),
+            3 => (parts[0], parts[1], parts[2]),
+            _ => return Err(format!("invalid range {:?}", e)),
+        };
+        if step == 0.0 || (last - first)*step < 0.0 {
+            return Err(format!("range {:?} does not reach its end", e));
+        }
+        let num = ((last - first)/step + 1e-9).floor() as usize;
+        for i in 0..num + 1 {
+            values.push((first + i as f64*step).to_string());
+        }
+    }
+    Ok(values)
+}
+
+/// Expands the values given for any of `flags` into a sweep,
+/// returning every combination of arguments.  A flag may be given by
+/// its long name, an alias or its short name, as listed in
+/// `param_flags`.
+fn sweep_args(args: Vec<OsString>, flags: &[String], param_flags: &ParamFlags)
+              -> Result<Vec<Vec<OsString>>, ClapMeError> {
+    let mut argvs: Vec<Vec<OsString>> = vec![Vec::new()];
+    let mut value_for: Option<String> = None;
+    let mut done = false;
+    for arg in args {
+        let s = arg.to_string_lossy().into_owned();
+        let choices: Vec<OsString> = if done {
+            vec![arg]
+        } else if let Some(name) = value_for.take() {
+            sweep_values(&s).map_err(|e| ClapMeError::invalid_value(&name, &s, e))?
+                .into_iter().map(OsString::from).collect()
+        } else if s == "--" {
+            done = true;
+            vec![arg]
+        } else {
+            // The flag, and any value given within the same argument,
+            // as in `--name=value`, `-nvalue` or `-n=value`.
+            let (flag, value) = if s.starts_with("--") {
+                match s.find('=') {
+                    Some(eq) => (&s[..eq], Some(&s[eq+1..])),
+                    None => (&s[..], None),
+                }
+            } else if s.starts_with('-') && s.chars().count() > 1 {
+                let end = s.char_indices().nth(2).map_or(s.len(), |(i, _)| i);
+                let value = &s[end..];
+                let value = value.strip_prefix('=').unwrap_or(value);
+                (&s[..end], if s.len() > end { Some(value) } else { None })
+            } else {
+                ("", None)
+            };
+            match param_flags.names.get(flag) {
+                Some(name) if flags.contains(name) => match value {
+                    Some(value) => {
+                        sweep_values(value)
+                            .map_err(|e| ClapMeError::invalid_value(name, value, e))?
+                            .into_iter().map(|v| OsString::from(format!("--{}={}", name, v)))
+                            .collect()
+                    },
+                    None => {
+                        value_for = Some(name.clone());
+                        vec![arg]
+                    },
+                },
+                _ => vec![arg],
+            }
+        };
+        argvs = argvs.iter().flat_map(|argv| choices.iter().map(move |c| {
+            let mut argv = argv.clone();
+            argv.push(c.clone());
+            argv
+        })).collect();
+    }
+    Ok(argvs)
+}
+
+/// Reads the arguments from a parameters file.
+fn read_params(path: &std::path::Path) -> Result<Vec<OsString>, ClapMeError> {
+    use std::io::Read;
+    let mut contents = String::new();
+    std::fs::File::open(path).and_then(|mut f| f.read_to_string(&mut contents))
+        .map_err(|e| ClapMeError::invalid_value("load-params", &path.to_string_lossy(), e))?;
+    Ok(contents.lines()
+       .map(|l| l.trim())
+       .filter(|l| !l.is_empty() && !l.starts_with('#'))
+       .map(OsString::from)
+       .collect())
+}
+
+/// How each flag is given on the command line, which is collected
+/// by `with_clap` so that a parameters file can be merged with the
+/// command line.
+#[doc(hidden)]
+#[derive(Debug, Default)]
+pub struct ParamFlags {
+    /// The name of the argument for each way of writing its flag,
+    /// such as `--name`, `--alias` or `-n`.
+    names: HashMap<String, String>,
+    /// The number of values following each flag, or `None` for any
+    /// number.  A flag that is missing here takes no values.
+    values: HashMap<String, Option<usize>>,
+    /// The flags that each flag cannot be used with.
+    conflicts: HashMap<String, Vec<String>>,
+    /// The flags that may be given more than once.
+    repeated: HashSet<String>,
+    subcommands: HashSet<String>,
+}
+
+impl ParamFlags {
+    /// How each flag of `T` is given, when it is the argument `name`.
+    fn of<T: ClapMe>(name: &str) -> Self {
+        let param_flags = std::cell::RefCell::new(ParamFlags::default());
+        let mut info = ArgInfo::new(name);
+        info.param_flags = Some(&param_flags);
+        T::with_clap(info, clap::App::new(""), |_| ());
+        param_flags.into_inner()
+    }
+
+    fn add_flag(&mut self, name: &str, short: Option<char>, aliases: &[&str]) {
+        self.names.insert(format!("--{}", name), name.to_string());
+        for a in aliases {
+            self.names.insert(format!("--{}", a), name.to_string());
+        }
+        if let Some(c) = short {
+            self.names.insert(format!("-{}", c), name.to_string());
+        }
+    }
+
+    /// The arguments giving the flag `name`, as it was `given`.  A
+    /// flag taking any number of values has each value given in its
+    /// own `--name=value`, so that it cannot take up the positional
+    /// arguments or subcommand that follow it.
+    fn flag_args(&self, name: &str, given: Vec<OsString>) -> Vec<OsString> {
+        if given.len() < 2 || self.values.get(name) != Some(&None) {
+            return given;
+        }
+        given.into_iter().skip(1).flat_map(|v| value_args(name, &v)).collect()
+    }
+}
+
+/// Command-line arguments split into the flags (each with all of the
+/// arguments that give it), the positional arguments, and a
+/// subcommand along with its own arguments.
+struct ParsedArgs {
+    flags: Vec<(String, Vec<OsString>)>,
+    positionals: Vec<OsString>,
+    subcommand: Option<(OsString, Vec<OsString>)>,
+}
+
+impl ParsedArgs {
+    fn new(args: Vec<OsString>, param_flags: &ParamFlags) -> Self {
+        let mut parsed = ParsedArgs { flags: Vec::new(), positionals: Vec::new(), subcommand: None };
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let s = arg.to_string_lossy().into_owned();
+            if s == "--" {
+                parsed.positionals.extend(args);
+                break;
+            }
+            let flag = if s.starts_with("--") {
+                s.split('=').next().unwrap().to_string()
+            } else {
+                s.chars().take(2).collect()
+            };
+            let name = match param_flags.names.get(&flag) {
+                Some(name) => name.clone(),
+                None if s.starts_with("--") => flag[2..].to_string(),
+                None if param_flags.subcommands.contains(&s) => {
+                    parsed.subcommand = Some((arg, args.collect()));
+                    break;
+                },
+                None => {
+                    parsed.positionals.push(arg);
+                    continue;
+                },
+            };
+            // A value given within the same argument, as in
+            // `--name=value` or `-nvalue`, is all there is.
+            let values = if s.len() > flag.len() {
+                Some(0)
+            } else {
+                param_flags.values.get(&name).cloned().unwrap_or(Some(0))
+            };
+            let mut given = vec![arg];
+            match values {
+                Some(n) => given.extend(args.by_ref().take(n)),
+                None => while args.peek().map_or(false, |a| !a.to_string_lossy().starts_with('-')) {
+                    given.push(args.next().unwrap());
+                },
+            }
+            parsed.flags.push((name, given));
+        }
+        parsed
+    }
+
+    /// Merges `self`, read from a parameters file, with `over`, whose
+    /// flags, positional arguments and subcommand take the place of
+    /// those in `self`.
+    fn merge(self, over: ParsedArgs, param_flags: &ParamFlags) -> Vec<OsString> {
+        let mut replaced: HashSet<&str> = HashSet::new();
+        for &(ref name, _) in over.flags.iter() {
+            replaced.insert(name);
+            if let Some(c) = param_flags.conflicts.get(name) {
+                replaced.extend(c.iter().map(|c| c.as_str()));
+            }
+        }
+        let mut merged: Vec<OsString> = self.flags.into_iter()
+            .filter(|&(ref name, _)| !replaced.contains(name.as_str()))
+            .flat_map(|(name, given)| param_flags.flag_args(&name, given))
+            .collect();
+        merged.extend(over.flags.into_iter()
+                      .flat_map(|(name, given)| param_flags.flag_args(&name, given)));
+        let positionals = if over.positionals.is_empty() {
+            self.positionals
+        } else {
+            over.positionals
+        };
+        let subcommand = match (self.subcommand, over.subcommand) {
+            (Some((name, args)), Some((over_name, over_args))) if name == over_name => {
+                let args = ParsedArgs::new(args, param_flags)
+                    .merge(ParsedArgs::new(over_args, param_flags), param_flags);
+                Some((name, args))
+            },
+            (subcommand, None) => subcommand,
+            (_, subcommand) => subcommand,
+        };
+        if subcommand.is_none() && positionals.iter().any(|a| a.to_string_lossy().starts_with('-')) {
+            merged.push(OsString::from("--"));
+        }
+        merged.extend(positionals);
+        if let Some((name, args)) = subcommand {
+            merged.push(name);
+            merged.extend(args);
+        }
+        merged
+    }
+}
+
+/// Merges the arguments from any `--load-params` file with the
+/// command-line arguments.  A flag given on the command line takes
+/// the place of every time that flag is given in the file, along
+/// with its values, and positional arguments on the command line take
+/// the place of those in the file.  A `--load-params` with no file is
+/// left for clap to report.
+fn merge_params(args: Vec<OsString>, param_flags: &ParamFlags) -> Result<Vec<OsString>, ClapMeError> {
+    let mut args = args.into_iter();
+    let program: Vec<OsString> = args.by_ref().take(1).collect();
+    let given = ParsedArgs::new(args.collect(), param_flags);
+    let files: Vec<OsString> = given.flags.iter()
+        .filter(|&&(ref name, _)| name == "load-params")
+        .filter_map(|&(_, ref given)| match given.get(1) {
+            Some(f) => Some(f.clone()),
+            None => {
+                let arg = given[0].to_string_lossy();
+                if arg.starts_with("--load-params=") {
+                    Some(OsString::from(&arg["--load-params=".len()..]))
+                } else {
+                    None
+                }
+            },
+        })
+        .collect();
+    let mut params: Vec<OsString> = Vec::new();
+    for f in files {
+        params = ParsedArgs::new(params, param_flags)
+            .merge(ParsedArgs::new(read_params(f.as_ref())?, param_flags), param_flags);
+    }
+    let mut merged = program;
+    merged.extend(ParsedArgs::new(params, param_flags).merge(given, param_flags));
+    Ok(merged)
+}
+
+/// The deepest that the recursive fields of a type such as a tree are
+/// nested on the command line.  A type with more than two recursive
+/// fields is nested less deeply, so that it has no more flags than a
+/// binary tree.
+const RECURSION_LIMIT: u32 = 3;
+
+/// Is the argument `name` nested too deeply within the recursive
+/// `fields` of a struct to be given on the command line?  A recursive
+/// type would otherwise need endless flags, so its recursive fields
+/// are left out below `RECURSION_LIMIT` levels.
+#[doc(hidden)]
+pub fn too_deep(name: &str, fields: &[&str]) -> bool {
+    let segments: Vec<&str> = name.split('-').collect();
+    let depth: usize = fields.iter().map(|f| {
+        let f: Vec<&str> = f.split('-').collect();
+        segments.windows(f.len()).filter(|w| *w == &f[..]).count()
+    }).sum();
+    depth > RECURSION_LIMIT as usize
+        || (fields.len() as f64).powi(depth as i32) > 2f64.powi(RECURSION_LIMIT as i32)
+}
+
+/// Parses the value given by `#[clapme(default_value = "...")]` for
+/// the argument `name`, just as if it had been given on the command
+/// line.  A default holding several values, such as `1 2` for a
+/// tuple, is split at whitespace unless it parses as a single value.
+#[doc(hidden)]
+pub fn parse_default_value<C: ClapMe>(name: &str, value: &str) -> Result<C, ClapMeError> {
+    let values: Vec<&str> = value.split_whitespace().collect();
+    let parsed = match parse_with_app(clap::App::new("default"), &["", "--", value]) {
+        Err(_) if values.len() > 1 => {
+            let mut args = vec!["", "--"];
+            args.extend(values);
+            parse_with_app(clap::App::new("default"), args)
+        },
+        parsed => parsed,
+    };
+    parsed.map_err(|e| ClapMeError::invalid_value(name, value, e))
+}
+
+fn parse_with_app<C: ClapMe, I, T>(app: clap::App, args: I) -> Result<C, ClapMeError>
+    where
+    I: IntoIterator<Item = T>,
+    T: Into<OsString> + Clone,
+{
+    C::with_clap(ArgInfo::new(""), app,
+                 |a| {
+                     let matches = a.get_matches_from_safe(args)?;
+                     C::from_clap("", &matches)
+                 })
+}
+
 impl ClapMe for bool {
     fn with_clap<T>(info: ArgInfo, app: clap::App,
                     f: impl FnOnce(clap::App) -> T) -> T {
-        let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-        f(app.arg(clap::Arg::with_name(info.name).long(info.name)
-                  .requires_all(info.required_flags)
-                  .conflicts_with_all(&conflicts)
-                  .help(&info.help)))
+        let arg = if info.optional {
+            info.arg()
+                .takes_value(true)
+                .min_values(0)
+                .max_values(1)
+                .require_equals(true)
+                .value_name("BOOL")
+                .validator(|s| str_to_bool(&s).map(|_| ()))
+        } else {
+            info.arg()
+        };
+        // In a `Vec` of structs, the flag is given once for each element.
+        let arg = arg.multiple(info.multiple);
+        if info.multiple {
+            info.repeatable();
+        }
+        if info.negatable && !info.name.is_empty() {
+            let negation = negated_name(info.name);
+            if let Some(flags) = info.param_flags {
+                flags.borrow_mut().add_flag(&negation, None, &[]);
+            }
+            let help = format!("Turns off --{}", info.name);
+            info.with_arg(app.arg(clap::Arg::with_name(&negation).long(&negation)
+                                  .help(&help)
+                                  .multiple(info.multiple)
+                                  .overrides_with(info.name)),
+                          arg.overrides_with(&negation), f)
+        } else {
+            info.with_arg(app, arg, f)
+        }
+    }
+    fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+        Ok(Self::from_clap_optional(name, matches)?.unwrap_or(false))
     }
-    fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-        Some(matches.is_present(name))
+    fn from_clap_optional(name: &str, matches: &clap::ArgMatches)
+                          -> Result<Option<Self>, ClapMeError> {
+        if matches.is_present(name) {
+            return match matches.value_of(name) {
+                Some(s) => str_to_bool(s).map(Some)
+                    .map_err(|e| ClapMeError::invalid_value(name, s, e)),
+                None => Ok(Some(true)),
+            };
+        }
+        if matches.is_present(negated_name(name)) {
+            return Ok(Some(false));
+        }
+        match matches.value_of(env_fallback(name)) {
+            Some(s) => str_to_bool(s).map(Some).map_err(|e| ClapMeError::invalid_value(name, s, e)),
+            None => Ok(None),
+        }
     }
     fn requires_flags(_name: &str) -> Vec<String> {
         vec![]
     }
+    fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+        if *self {
+            vec![OsString::from(format!("--{}", name))]
+        } else {
+            Vec::new()
+        }
+    }
+    fn to_clap_args_optional(&self, name: &str) -> Vec<OsString> {
+        vec![OsString::from(format!("--{}={}", name, self))]
+    }
+    fn to_help_value(&self) -> String {
+        self.to_string()
+    }
+    fn arg_names(name: &str) -> Vec<String> {
+        vec![name.to_string(), negated_name(name)]
+    }
+}
+
+/// The name of the flag that turns off the `bool` flag `name`.
+fn negated_name(name: &str) -> String {
+    format!("no-{}", name)
+}
+
+/// A flag that may be given more than once, such as `-vvv`, holding
+/// the number of times it was given.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Count(pub u8);
+
+impl ClapMe for Count {
+    fn with_clap<T>(info: ArgInfo, app: clap::App,
+                    f: impl FnOnce(clap::App) -> T) -> T {
+        info.repeatable();
+        info.with_arg(app, info.arg().multiple(true), f)
+    }
+    fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+        if matches.is_present(name) {
+            let n = matches.occurrences_of(name);
+            return Ok(Count(if n > 255 { 255 } else { n as u8 }));
+        }
+        match matches.value_of(env_fallback(name)) {
+            Some(s) => u8::from_str(s).map(Count)
+                .map_err(|e| ClapMeError::invalid_value(name, s, e)),
+            None => Ok(Count(0)),
+        }
+    }
+    fn requires_flags(_name: &str) -> Vec<String> {
+        vec![]
+    }
+    fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+        (0..self.0).map(|_| OsString::from(format!("--{}", name))).collect()
+    }
+}
+
+/// A number of bytes, which may be given with SI units as in `10MB`
+/// or `512k`, or with binary units as in `4GiB`.  It is displayed in
+/// the largest unit that represents it exactly, so it can be read
+/// back in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct ByteSize(pub u64);
+
+/// The units understood in a `ByteSize`, as they are written.
+const BYTE_UNITS: &[(&str, u64)] = &[
+    ("EiB", 1 << 60),
+    ("PiB", 1 << 50),
+    ("TiB", 1 << 40),
+    ("GiB", 1 << 30),
+    ("MiB", 1 << 20),
+    ("KiB", 1 << 10),
+    ("EB", 1_000_000_000_000_000_000),
+    ("PB", 1_000_000_000_000_000),
+    ("TB", 1_000_000_000_000),
+    ("GB", 1_000_000_000),
+    ("MB", 1_000_000),
+    ("kB", 1_000),
+];
+
+impl FromStr for ByteSize {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let split = s.rfind(|c: char| !c.is_alphabetic())
+            .map(|i| i + s[i..].chars().next().unwrap().len_utf8())
+            .unwrap_or(0);
+        let (amount, unit) = s.split_at(split);
+        let lower = unit.to_lowercase();
+        let prefix = if lower.ends_with('b') { &lower[..lower.len()-1] } else { &lower[..] };
+        let (prefix, base) = if prefix.ends_with('i') {
+            (&prefix[..prefix.len()-1], 1024)
+        } else {
+            (prefix, 1000)
+        };
+        let power = match prefix {
+            "" if base == 1000 => 0,
+            "k" => 1,
+            "m" => 2,
+            "g" => 3,
+            "t" => 4,
+            "p" => 5,
+            "e" => 6,
+            _ => return Err(format!("unknown unit {:?}", unit)),
+        };
+        let multiplier: u64 = (0..power).fold(1, |m, _| m*base);
+        if let Ok(x) = amount.trim().parse::<u64>() {
+            return x.checked_mul(multiplier).map(ByteSize)
+                .ok_or_else(|| "size is too large".to_string());
+        }
+        let x = str_to_f64(amount)?*multiplier as f64;
+        if !(x >= 0.0) || x >= u64::max_value() as f64 || x.fract() != 0.0 {
+            return Err(format!("{:?} is not a whole number of bytes", s));
+        }
+        Ok(ByteSize(x as u64))
+    }
+}
+
+impl fmt::Display for ByteSize {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let best = BYTE_UNITS.iter().filter(|&&(_, size)| self.0 != 0 && self.0 % size == 0)
+            .min_by_key(|&&(_, size)| self.0 / size);
+        match best {
+            Some(&(name, size)) => write!(f, "{}{}", self.0 / size, name),
+            None => write!(f, "{}B", self.0),
+        }
+    }
 }
 
 macro_rules! impl_fromstr {
@@ -143,88 +1413,43 @@ macro_rules! impl_fromstr {
         impl ClapMe for $t {
             fn with_clap<T>(info: ArgInfo, app: clap::App,
                             f: impl FnOnce(clap::App) -> T) -> T {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                let ruo: Vec<_> = info.required_unless_one.iter().map(AsRef::as_ref).collect();
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .help(&info.help)
-                              .validator(|s| Self::from_str(&s).map(|_| ())
-                                         .map_err(|e| e.to_string()))))
-                } else if ruo.len() > 0 {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .required_unless_one(&ruo)
-                              .help(&info.help)
-                              .validator(|s| Self::from_str(&s).map(|_| ())
-                                         .map_err(|e| e.to_string()))))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .help(&info.help)
-                              .validator(|s| Self::from_str(&s).map(|_| ())
-                                         .map_err(|e| e.to_string()))))
-                }
+                info.with_arg(app, info.value_arg($tyname)
+                                   .validator(|s| Self::from_str(&s).map(|_| ())
+                                              .map_err(|e| e.to_string())), f)
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
                 // println!("from {} {:?}", name, matches.value_of(name));
-                matches.value_of(name).map(|s| Self::from_str(s).unwrap())
+                match matches.value_of(lookup_name(matches, name)) {
+                    Some(s) => Self::from_str(s)
+                        .map_err(|e| ClapMeError::invalid_value(name, s, e)),
+                    None => Err(ClapMeError::missing(name)),
+                }
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                value_args(name, self.to_string().as_ref())
             }
         }
 
-        impl ClapMe for Vec<$t> {
-            fn with_clap<TT>(info: ArgInfo, app: clap::App,
-                             f: impl FnOnce(clap::App) -> TT) -> TT {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .multiple(true)
-                              .help(&info.help)
-                              .validator(|s| <$t>::from_str(&s).map(|_| ())
-                                         .map_err(|_| "oops".to_owned()))))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .conflicts_with_all(&conflicts)
-                              .multiple(true)
-                              .help(&info.help)
-                              .validator(|s| <$t>::from_str(&s).map(|_| ())
-                                         .map_err(|_| "oops".to_owned()))))
-                }
+        impl ClapMeValue for $t {
+            fn value_name() -> &'static str {
+                $tyname
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-                Some(matches.values_of(name).unwrap_or(clap::Values::default())
-                     .map(|s| <$t>::from_str(s).unwrap()).collect())
+            fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+                Self::from_str(os_to_str(value)?).map_err(|e| e.to_string())
             }
-            fn requires_flags(_name: &str) -> Vec<String> {
-                vec![]
+            fn to_value(&self) -> OsString {
+                OsString::from(self.to_string())
             }
         }
     }
 }
 
 fn str_to_f64(s: &str) -> Result<f64, String> {
+    // meval knows nothing of "inf" or "NaN", which is how `to_args`
+    // writes the values that are not finite.
+    if let Ok(x) = f64::from_str(s) {
+        return Ok(x);
+    }
     meval::eval_str(s).map_err(|e| e.to_string())
 }
 
@@ -233,77 +1458,32 @@ macro_rules! impl_rustyard {
         impl ClapMe for $t {
             fn with_clap<T>(info: ArgInfo, app: clap::App,
                             f: impl FnOnce(clap::App) -> T) -> T {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                let ruo: Vec<_> = info.required_unless_one.iter().map(AsRef::as_ref).collect();
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .help(&info.help)
-                              .validator(|s| str_to_f64(&s).map(|_| ()))))
-                } else if ruo.len() > 0 {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .required_unless_one(&ruo)
-                              .help(&info.help)
-                              .validator(|s| str_to_f64(&s).map(|_| ()))))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .help(&info.help)
-                              .validator(|s| str_to_f64(&s).map(|_| ()))))
-                }
+                info.sweepable();
+                info.with_arg(app, info.value_arg($tyname)
+                                   .validator(|s| str_to_f64(&s).map(|_| ())), f)
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
                 // println!("from {} {:?}", name, matches.value_of(name));
-                matches.value_of(name).map(|s| str_to_f64(s).unwrap() as Self)
+                match matches.value_of(lookup_name(matches, name)) {
+                    Some(s) => str_to_f64(s).map(|x| x as Self)
+                        .map_err(|e| ClapMeError::invalid_value(name, s, e)),
+                    None => Err(ClapMeError::missing(name)),
+                }
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                value_args(name, self.to_string().as_ref())
             }
         }
 
-        impl ClapMe for Vec<$t> {
-            fn with_clap<TT>(info: ArgInfo, app: clap::App,
-                             f: impl FnOnce(clap::App) -> TT) -> TT {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .multiple(true)
-                              .help(&info.help)
-                              .validator(|s| str_to_f64(&s).map(|_| ()))))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .conflicts_with_all(&conflicts)
-                              .multiple(true)
-                              .help(&info.help)
-                              .validator(|s| str_to_f64(&s).map(|_| ()))))
-                }
+        impl ClapMeValue for $t {
+            fn value_name() -> &'static str {
+                $tyname
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-                Some(matches.values_of(name).unwrap_or(clap::Values::default())
-                     .map(|s| str_to_f64(s).unwrap() as $t).collect())
+            fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+                str_to_f64(os_to_str(value)?).map(|x| x as Self)
             }
-            fn requires_flags(_name: &str) -> Vec<String> {
-                vec![]
+            fn to_value(&self) -> OsString {
+                OsString::from(self.to_string())
             }
         }
     }
@@ -311,6 +1491,8 @@ macro_rules! impl_rustyard {
 impl_rustyard!(f32, "FLOAT");
 impl_rustyard!(f64, "FLOAT");
 
+impl_fromstr!(char, "CHAR");
+impl_fromstr!(ByteSize, "SIZE");
 impl_fromstr!(std::net::IpAddr, "ADDR");
 impl_fromstr!(std::net::Ipv4Addr, "ADDR");
 impl_fromstr!(std::net::Ipv6Addr, "ADDR");
@@ -323,8 +1505,7 @@ macro_rules! impl_fromstr_or_via_f64 {
         impl ClapMe for $t {
             fn with_clap<T>(info: ArgInfo, app: clap::App,
                             f: impl FnOnce(clap::App) -> T) -> T {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                let ruo: Vec<_> = info.required_unless_one.iter().map(AsRef::as_ref).collect();
+                info.sweepable();
                 let validator = |s: String| {
                     if let Err(e) = Self::from_str(&s) {
                         if let Ok(x) = str_to_f64(&s) {
@@ -336,171 +1517,131 @@ macro_rules! impl_fromstr_or_via_f64 {
                     }
                     Ok(())
                 };
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .help(&info.help)
-                              .validator(validator)))
-                } else if ruo.len() > 0 {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .required_unless_one(&ruo)
-                              .help(&info.help)
-                              .validator(validator)))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .help(&info.help)
-                              .validator(validator)))
-                }
+                info.with_arg(app, info.value_arg($tyname).validator(validator), f)
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
                 // println!("from {} {:?}", name, matches.value_of(name));
-                matches.value_of(name).map(|s| {
-                    if let Ok(v) = Self::from_str(s) {
-                        return v;
-                    }
-                    str_to_f64(s).unwrap() as Self
-                })
+                match matches.value_of(lookup_name(matches, name)) {
+                    Some(s) => Self::from_str(s).or_else(|e| {
+                        match str_to_f64(s) {
+                            Ok(x) if (x as $t) as f64 == x => Ok(x as $t),
+                            _ => Err(ClapMeError::invalid_value(name, s, e)),
+                        }
+                    }),
+                    None => Err(ClapMeError::missing(name)),
+                }
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                value_args(name, self.to_string().as_ref())
             }
         }
 
-        impl ClapMe for Vec<$t> {
-            fn with_clap<TT>(info: ArgInfo, app: clap::App,
-                             f: impl FnOnce(clap::App) -> TT) -> TT {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .multiple(true)
-                              .help(&info.help)
-                              .validator(|s| <$t>::from_str(&s).map(|_| ())
-                                         .map_err(|_| "oops".to_owned()))))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .conflicts_with_all(&conflicts)
-                              .multiple(true)
-                              .help(&info.help)
-                              .validator(|s| <$t>::from_str(&s).map(|_| ())
-                                         .map_err(|_| "oops".to_owned()))))
-                }
+        impl ClapMeValue for $t {
+            fn value_name() -> &'static str {
+                $tyname
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-                Some(matches.values_of(name).unwrap_or(clap::Values::default())
-                     .map(|s| <$t>::from_str(s).unwrap()).collect())
+            fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+                let s = os_to_str(value)?;
+                Self::from_str(s).or_else(|e| {
+                    match str_to_f64(s) {
+                        Ok(x) if (x as $t) as f64 == x => Ok(x as $t),
+                        _ => Err(e.to_string()),
+                    }
+                })
             }
-            fn requires_flags(_name: &str) -> Vec<String> {
-                vec![]
+            fn to_value(&self) -> OsString {
+                OsString::from(self.to_string())
             }
         }
     }
 }
 
+impl_fromstr_or_via_f64!(i8, "INT");
 impl_fromstr_or_via_f64!(i16, "INT");
 impl_fromstr_or_via_f64!(isize, "INT");
 impl_fromstr_or_via_f64!(i32, "INT");
 impl_fromstr_or_via_f64!(i64, "INT");
 impl_fromstr_or_via_f64!(i128, "INT");
+impl_fromstr_or_via_f64!(u8, "INT");
 impl_fromstr_or_via_f64!(u16, "INT");
 impl_fromstr_or_via_f64!(u32, "INT");
 impl_fromstr_or_via_f64!(u64, "INT");
 impl_fromstr_or_via_f64!(u128, "INT");
 impl_fromstr_or_via_f64!(usize, "INT");
 
+macro_rules! impl_nonzero {
+    ($t:ty, $base:ty) => {
+        impl ClapMe for $t {
+            fn with_clap<T>(info: ArgInfo, app: clap::App,
+                            f: impl FnOnce(clap::App) -> T) -> T {
+                <$base>::with_clap(info, app, f)
+            }
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+                let x = <$base>::from_clap(name, matches)?;
+                Self::new(x).ok_or_else(|| {
+                    ClapMeError::invalid_value(name, &x.to_string(), "must not be zero")
+                })
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                self.get().to_clap_args(name)
+            }
+        }
+
+        impl ClapMeValue for $t {
+            fn value_name() -> &'static str {
+                <$base>::value_name()
+            }
+            fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+                Self::new(<$base>::parse_value(value)?)
+                    .ok_or_else(|| "must not be zero".to_string())
+            }
+            fn to_value(&self) -> OsString {
+                self.get().to_value()
+            }
+        }
+    }
+}
+
+impl_nonzero!(std::num::NonZeroI8, i8);
+impl_nonzero!(std::num::NonZeroI16, i16);
+impl_nonzero!(std::num::NonZeroI32, i32);
+impl_nonzero!(std::num::NonZeroI64, i64);
+impl_nonzero!(std::num::NonZeroI128, i128);
+impl_nonzero!(std::num::NonZeroIsize, isize);
+impl_nonzero!(std::num::NonZeroU8, u8);
+impl_nonzero!(std::num::NonZeroU16, u16);
+impl_nonzero!(std::num::NonZeroU32, u32);
+impl_nonzero!(std::num::NonZeroU64, u64);
+impl_nonzero!(std::num::NonZeroU128, u128);
+impl_nonzero!(std::num::NonZeroUsize, usize);
+
 macro_rules! impl_from {
     ($t:ty, $tyname:expr) => {
         impl ClapMe for $t {
             fn with_clap<T>(info: ArgInfo, app: clap::App,
                             f: impl FnOnce(clap::App) -> T) -> T {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                let ruo: Vec<_> = info.required_unless_one.iter().map(AsRef::as_ref).collect();
                 // println!("\n\nmy name is {:?}", info.name);
                 // println!("   requires_all {:?}", info.required_flags);
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .help(&info.help)))
-                } else if ruo.len() > 0 {
-                    // println!("   {} conflicts_with_all {:?}", info.name, &conflicts);
-                    // println!("   {} required_unless_one {:?}", info.name, &ruo);
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .required_unless_one(&ruo)
-                              .help(&info.help)))
-                } else {
-                    // println!("   {} conflicts_with_all {:?}", info.name, &conflicts);
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .help(&info.help)))
-                }
+                info.with_arg(app, info.value_arg($tyname), f)
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-                matches.value_of(name).map(|s| Self::from(s))
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+                matches.value_of(lookup_name(matches, name)).map(|s| Self::from(s))
+                    .ok_or_else(|| ClapMeError::missing(name))
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                value_args(name, self.to_string().as_ref())
             }
         }
 
-        impl ClapMe for Vec<$t> {
-            fn with_clap<TT>(info: ArgInfo, app: clap::App,
-                             f: impl FnOnce(clap::App) -> TT) -> TT {
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .multiple(true)
-                              .help(&info.help)))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .multiple(true)
-                              .help(&info.help)))
-                }
+        impl ClapMeValue for $t {
+            fn value_name() -> &'static str {
+                $tyname
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-                Some(matches.values_of(name).unwrap_or(clap::Values::default())
-                     .map(|s| <$t>::from(s)).collect())
+            fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+                Ok(Self::from(os_to_str(value)?))
             }
-            fn requires_flags(_name: &str) -> Vec<String> {
-                vec![]
+            fn to_value(&self) -> OsString {
+                OsString::from(self.to_string())
             }
         }
     }
@@ -508,74 +1649,141 @@ macro_rules! impl_from {
 
 impl_from!(String, "STRING");
 
+/// The units understood in a `Duration`, with their length in
+/// nanoseconds.  The first name given for each length is the one we
+/// write.
+const DURATION_UNITS: &[(&str, u128)] = &[
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("hr", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("min", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("sec", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("µs", 1_000),
+    ("ns", 1),
+];
+
+/// The length of a duration given as an amount of `unit`, which may
+/// be an expression.
+fn duration_nanos(amount: &str, unit: u128) -> Result<u128, String> {
+    let amount = amount.trim();
+    if let Ok(x) = amount.parse::<u128>() {
+        return x.checked_mul(unit).ok_or_else(|| "duration is too long".to_string());
+    }
+    let x = str_to_f64(amount)?*unit as f64;
+    if !(x >= 0.0) || x >= u128::max_value() as f64 {
+        return Err(format!("invalid duration {:?}", amount));
+    }
+    Ok(x.round() as u128)
+}
+
+/// Parses a duration such as `90s`, `5m30s`, `1.5h` or `2*60s`.  A
+/// number with no unit is in seconds.
+fn str_to_duration(s: &str) -> Result<std::time::Duration, String> {
+    let mut nanos: u128 = 0;
+    let mut start = 0;
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if !c.is_alphabetic() {
+            continue;
+        }
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, c)) = chars.peek() {
+            if !c.is_alphabetic() {
+                break;
+            }
+            end = j + c.len_utf8();
+            chars.next();
+        }
+        let at_boundary = match chars.peek() {
+            None => true,
+            Some(&(_, c)) => c.is_digit(10) || c == '.' || c == ' ',
+        };
+        let unit = DURATION_UNITS.iter().find(|&&(u, _)| u == &s[i..end]);
+        if let (true, Some(&(_, unit))) = (at_boundary, unit) {
+            nanos += duration_nanos(&s[start..i], unit)?;
+            start = end;
+        }
+    }
+    if !s[start..].trim().is_empty() || start == 0 {
+        nanos += duration_nanos(&s[start..], 1_000_000_000)?;
+    }
+    let secs = nanos / 1_000_000_000;
+    if secs > u64::max_value() as u128 {
+        return Err("duration is too long".to_string());
+    }
+    Ok(std::time::Duration::new(secs as u64, (nanos % 1_000_000_000) as u32))
+}
+
+/// Writes a duration in the largest unit that represents it exactly.
+fn duration_to_string(d: &std::time::Duration) -> String {
+    let nanos = d.as_secs() as u128*1_000_000_000 + d.subsec_nanos() as u128;
+    if nanos == 0 {
+        return "0s".to_string();
+    }
+    let &(name, unit) = DURATION_UNITS.iter().find(|&&(_, unit)| nanos % unit == 0).unwrap();
+    format!("{}{}", nanos / unit, name)
+}
+
+impl ClapMe for std::time::Duration {
+    fn with_clap<T>(info: ArgInfo, app: clap::App,
+                    f: impl FnOnce(clap::App) -> T) -> T {
+        info.with_arg(app, info.value_arg("DURATION")
+                           .validator(|s| str_to_duration(&s).map(|_| ())), f)
+    }
+    fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+        match matches.value_of(lookup_name(matches, name)) {
+            Some(s) => str_to_duration(s)
+                .map_err(|e| ClapMeError::invalid_value(name, s, e)),
+            None => Err(ClapMeError::missing(name)),
+        }
+    }
+    fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+        value_args(name, duration_to_string(self).as_ref())
+    }
+}
+
+impl ClapMeValue for std::time::Duration {
+    fn value_name() -> &'static str {
+        "DURATION"
+    }
+    fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+        str_to_duration(os_to_str(value)?)
+    }
+    fn to_value(&self) -> OsString {
+        OsString::from(duration_to_string(self))
+    }
+}
+
 macro_rules! impl_from_osstr {
     ($t:ty, $tyname:expr) => {
         impl ClapMe for $t {
             fn with_clap<T>(info: ArgInfo, app: clap::App,
                             f: impl FnOnce(clap::App) -> T) -> T {
-                let conflicts: Vec<_> = info.conflicted_flags.iter().map(AsRef::as_ref).collect();
-                let ruo: Vec<_> = info.required_unless_one.iter().map(AsRef::as_ref).collect();
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .help(&info.help)))
-                } else if ruo.len() > 0 {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .required_unless_one(&ruo)
-                              .help(&info.help)))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .requires_all(info.required_flags)
-                              .required(info.required)
-                              .conflicts_with_all(&conflicts)
-                              .help(&info.help)))
-                }
+                info.with_arg(app, info.value_arg($tyname), f)
+            }
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+                matches.value_of_os(lookup_name(matches, name)).map(|s| Self::from(s))
+                    .ok_or_else(|| ClapMeError::missing(name))
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-                matches.value_of_os(name).map(|s| Self::from(s))
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                value_args(name, self.as_ref())
             }
         }
 
-        impl ClapMe for Vec<$t> {
-            fn with_clap<TT>(info: ArgInfo, app: clap::App,
-                             f: impl FnOnce(clap::App) -> TT) -> TT {
-                if info.name == "" {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .multiple(true)
-                              .help(&info.help)))
-                } else {
-                    f(app.arg(clap::Arg::with_name(info.name)
-                              .long(info.name)
-                              .takes_value(true)
-                              .value_name($tyname)
-                              .required(false)
-                              .requires_all(info.required_flags)
-                              .multiple(true)
-                              .help(&info.help)))
-                }
+        impl ClapMeValue for $t {
+            fn value_name() -> &'static str {
+                $tyname
             }
-            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-                Some(matches.values_of_os(name).unwrap_or(clap::OsValues::default())
-                     .map(|s| <$t>::from(s)).collect())
+            fn parse_value(value: &std::ffi::OsStr) -> Result<Self, String> {
+                Ok(Self::from(value))
             }
-            fn requires_flags(_name: &str) -> Vec<String> {
-                vec![]
+            fn to_value(&self) -> OsString {
+                let value: &std::ffi::OsStr = self.as_ref();
+                value.to_os_string()
             }
         }
     }
@@ -589,26 +1797,294 @@ impl<T: ClapMe> ClapMe for Option<T> {
                      f: impl FnOnce(clap::App) -> TT) -> TT {
         info.required = false;
         info.required_unless_one = Vec::new();
+        info.optional = true;
         T::with_clap(info, app, f)
     }
-    fn from_clap(name: &str, matches: &clap::ArgMatches) -> Option<Self> {
-        Some(T::from_clap(name, matches))
+    fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+        T::from_clap_optional(name, matches)
     }
     fn requires_flags(_name: &str) -> Vec<String> {
         vec![]
     }
+    fn arg_names(name: &str) -> Vec<String> {
+        T::arg_names(name)
+    }
+    fn find_duplicate(name: &str, matches: &clap::ArgMatches) -> Option<String> {
+        T::find_duplicate(name, matches)
+    }
+    fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+        match *self {
+            Some(ref x) => x.to_clap_args_optional(name),
+            None => Vec::new(),
+        }
+    }
+    fn to_subcommand_args(&self) -> Vec<OsString> {
+        match *self {
+            Some(ref x) => x.to_subcommand_args(),
+            None => Vec::new(),
+        }
+    }
+    fn to_help_value(&self) -> String {
+        match *self {
+            Some(ref x) => x.to_help_value(),
+            None => String::new(),
+        }
+    }
+}
+
+
+/// Parses each value given for the argument `name` into any
+/// collection.
+fn parse_values<T, C>(name: &str, matches: &clap::ArgMatches) -> Result<C, ClapMeError>
+    where T: ClapMeValue, C: std::iter::FromIterator<T>
+{
+    matches.values_of_os(lookup_name(matches, name)).unwrap_or(clap::OsValues::default())
+        .map(|s| T::parse_value(s)
+             .map_err(|e| ClapMeError::invalid_value(name, &s.to_string_lossy(), e)))
+        .collect()
+}
+
+/// Implements `ClapMe` for a collection of values, which is given by
+/// repeating its flag.  A set (which is given the bounds on its
+/// values) reports any value that is given more than once.
+macro_rules! impl_collection {
+    ($c:ident) => {
+        impl_collection!(@impl $c []);
+    };
+    ($c:ident, $($bound:tt)+) => {
+        impl_collection!(@impl $c [+ $($bound)+]
+            fn find_duplicate(name: &str, matches: &clap::ArgMatches) -> Option<String> {
+                let mut seen = $c::new();
+                for s in matches.values_of_os(lookup_name(matches, name))? {
+                    if let Ok(x) = T::parse_value(s) {
+                        if !seen.insert(x) {
+                            return Some(s.to_string_lossy().into_owned());
+                        }
+                    }
+                }
+                None
+            }
+        );
+    };
+    (@impl $c:ident [$($bound:tt)*] $($extra:tt)*) => {
+        impl<T: ClapMeElement $($bound)*> ClapMe for $c<T> {
+            fn with_clap<TT>(info: ArgInfo, app: clap::App,
+                             f: impl FnOnce(clap::App) -> TT) -> TT {
+                T::with_clap_many(info, app, f)
+            }
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+                Ok(T::from_clap_many(name, matches)?.into_iter().collect())
+            }
+            fn requires_flags(_name: &str) -> Vec<String> {
+                vec![]
+            }
+            fn arg_names(name: &str) -> Vec<String> {
+                T::arg_names(name)
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                self.iter().flat_map(|x| x.to_clap_args_many(name)).collect()
+            }
+            $($extra)*
+        }
+    };
+}
+
+impl_collection!(Vec);
+impl_collection!(VecDeque);
+impl_collection!(HashSet, ClapMeValue + Eq + std::hash::Hash);
+impl_collection!(BTreeSet, ClapMeValue + Ord);
+
+/// Parses a `KEY=VALUE` pair for a map.
+fn parse_pair<K: ClapMeValue, V: ClapMeValue>(pair: &std::ffi::OsStr) -> Result<(K, V), String> {
+    let pair = os_to_str(pair)?;
+    match pair.find('=') {
+        Some(i) => {
+            let k = K::parse_value(pair[..i].as_ref())
+                .map_err(|e| format!("invalid key {:?}: {}", &pair[..i], e))?;
+            let v = V::parse_value(pair[i+1..].as_ref())
+                .map_err(|e| format!("invalid value {:?}: {}", &pair[i+1..], e))?;
+            Ok((k, v))
+        },
+        None => Err(format!("expected KEY=VALUE, found {:?}", pair)),
+    }
+}
+
+macro_rules! impl_map {
+    ($map:ident, $set:ident, $($bound:tt)+) => {
+        impl<K: ClapMeValue + $($bound)+, V: ClapMeValue> ClapMe for $map<K, V> {
+            fn with_clap<T>(info: ArgInfo, app: clap::App,
+                            f: impl FnOnce(clap::App) -> T) -> T {
+                let value_name = format!("{}={}", K::value_name(), V::value_name());
+                info.with_arg(app, info.values_arg(&value_name)
+                                   .validator_os(|s| parse_pair::<K, V>(s).map(|_| ())
+                                                 .map_err(OsString::from)), f)
+            }
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+                matches.values_of_os(lookup_name(matches, name)).unwrap_or(clap::OsValues::default())
+                    .map(|s| parse_pair(s)
+                         .map_err(|e| ClapMeError::invalid_value(name, &s.to_string_lossy(), e)))
+                    .collect()
+            }
+            fn requires_flags(_name: &str) -> Vec<String> {
+                vec![]
+            }
+            fn find_duplicate(name: &str, matches: &clap::ArgMatches) -> Option<String> {
+                let mut keys = $set::new();
+                for s in matches.values_of_os(lookup_name(matches, name))? {
+                    if let Ok((k, _)) = parse_pair::<K, V>(s) {
+                        if !keys.insert(k) {
+                            let s = s.to_string_lossy();
+                            return Some(s.split('=').next().unwrap_or("").to_string());
+                        }
+                    }
+                }
+                None
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                self.iter().flat_map(|(k, v)| {
+                    let mut pair = k.to_value();
+                    pair.push("=");
+                    pair.push(v.to_value());
+                    value_args(name, &pair)
+                }).collect()
+            }
+        }
+    }
+}
+
+impl_map!(HashMap, HashSet, Eq + std::hash::Hash);
+impl_map!(BTreeMap, BTreeSet, Ord);
+
+/// Implements `ClapMe` for a tuple of values, which takes one value
+/// for each element, as in `--origin 0 0`.
+macro_rules! impl_tuple {
+    ($($t:ident $i:tt),+) => {
+        impl<$($t: ClapMeValue),+> ClapMe for ($($t,)+) {
+            fn with_clap<TT>(info: ArgInfo, app: clap::App,
+                             f: impl FnOnce(clap::App) -> TT) -> TT {
+                let value_names = [$($t::value_name()),+];
+                info.with_arg(app, info.tuple_arg(&value_names), f)
+            }
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+                let values = tuple_values(name, matches, [$($i),+].len())?;
+                Ok(($(parse_position::<$t>(name, &values, $i)?,)+))
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                tuple_to_args(name, vec![$(self.$i.to_value()),+])
+            }
+        }
+    }
+}
+
+impl_tuple!(A 0, B 1);
+impl_tuple!(A 0, B 1, C 2);
+
+/// An array takes one value for each element, as in `--origin 0 0 1`.
+/// An empty array has no flag at all.
+impl<T: ClapMeValue, const N: usize> ClapMe for [T; N] {
+    fn with_clap<TT>(info: ArgInfo, app: clap::App,
+                     f: impl FnOnce(clap::App) -> TT) -> TT {
+        if N == 0 {
+            return f(app);
+        }
+        let value_names = [T::value_name(); N];
+        info.with_arg(app, info.tuple_arg(&value_names), f)
+    }
+    fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+        let values = if N == 0 { Vec::new() } else { tuple_values(name, matches, N)? };
+        let elements = (0..N).map(|i| parse_position(name, &values, i))
+            .collect::<Result<Vec<T>, ClapMeError>>()?;
+        <[T; N] as std::convert::TryFrom<Vec<T>>>::try_from(elements).map_err(|v| {
+            ClapMeError::invalid_value(name, "", format!("expected {} values, found {}",
+                                                         N, v.len()))
+        })
+    }
+    fn requires_flags(name: &str) -> Vec<String> {
+        if N == 0 { Vec::new() } else { vec![name.to_string()] }
+    }
+    fn arg_names(name: &str) -> Vec<String> {
+        Self::requires_flags(name)
+    }
+    fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+        if N == 0 {
+            return Vec::new();
+        }
+        tuple_to_args(name, self.iter().map(ClapMeValue::to_value).collect())
+    }
 }
 
+/// Checks for a value given more than once for a collection such as
+/// a map, which is an error if `error`, and otherwise a warning.
+#[doc(hidden)]
+pub fn check_duplicates<C: ClapMe>(name: &str, matches: &clap::ArgMatches, error: bool)
+                                   -> Result<(), ClapMeError> {
+    if let Some(d) = C::find_duplicate(name, matches) {
+        if error {
+            return Err(ClapMeError::invalid_value(name, &d, "given more than once"));
+        }
+        eprintln!("warning: {:?} given more than once for '--{}'", d, name);
+    }
+    Ok(())
+}
+
+macro_rules! impl_pointer {
+    ($p:ident) => {
+        impl<T: ClapMe> ClapMe for $p<T> {
+            fn with_clap<TT>(info: ArgInfo, app: clap::App,
+                             f: impl FnOnce(clap::App) -> TT) -> TT {
+                T::with_clap(info, app, f)
+            }
+            fn from_clap(name: &str, matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+                T::from_clap(name, matches).map($p::new)
+            }
+            fn from_clap_optional(name: &str, matches: &clap::ArgMatches)
+                                  -> Result<Option<Self>, ClapMeError> {
+                T::from_clap_optional(name, matches).map(|x| x.map($p::new))
+            }
+            fn requires_flags(name: &str) -> Vec<String> {
+                T::requires_flags(name)
+            }
+            fn arg_names(name: &str) -> Vec<String> {
+                T::arg_names(name)
+            }
+            fn find_duplicate(name: &str, matches: &clap::ArgMatches) -> Option<String> {
+                T::find_duplicate(name, matches)
+            }
+            fn to_clap_args(&self, name: &str) -> Vec<OsString> {
+                (**self).to_clap_args(name)
+            }
+            fn to_clap_args_optional(&self, name: &str) -> Vec<OsString> {
+                (**self).to_clap_args_optional(name)
+            }
+            fn to_subcommand_args(&self) -> Vec<OsString> {
+                (**self).to_subcommand_args()
+            }
+            fn to_help_value(&self) -> String {
+                (**self).to_help_value()
+            }
+            fn app<'a, 'b>() -> clap::App<'a, 'b> {
+                T::app()
+            }
+        }
+    }
+}
+
+impl_pointer!(Box);
+impl_pointer!(Rc);
+impl_pointer!(Arc);
 
 impl<T> ClapMe for std::marker::PhantomData<T> {
     fn with_clap<TT>(_info: ArgInfo, app: clap::App,
                      f: impl FnOnce(clap::App) -> TT) -> TT {
         f(app)
     }
-    fn from_clap(_name: &str, _matches: &clap::ArgMatches) -> Option<Self> {
-        Some(std::marker::PhantomData)
+    fn from_clap(_name: &str, _matches: &clap::ArgMatches) -> Result<Self, ClapMeError> {
+        Ok(std::marker::PhantomData)
     }
     fn requires_flags(_name: &str) -> Vec<String> {
         vec![]
     }
+    fn arg_names(_name: &str) -> Vec<String> {
+        vec![]
+    }
 }
@@ -0,0 +1,32 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn app_metadata() {
+    /// A program that does nothing.
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        verbose: bool,
+    }
+    let mut help = Vec::new();
+    Opt::app().write_help(&mut help).unwrap();
+    let help = String::from_utf8(help).unwrap();
+    println!("help: {}", help);
+    assert!(help.starts_with(&format!("clapme {}", env!("CARGO_PKG_VERSION"))));
+    assert!(help.contains(env!("CARGO_PKG_AUTHORS")));
+    assert!(help.contains("A program that does nothing."));
+
+    let e = Opt::from_iter(&["", "--version"]).unwrap_err();
+    assert_eq!(clapme::ClapMeErrorKind::Clap(clapme::clap::ErrorKind::VersionDisplayed),
+               e.kind);
+}
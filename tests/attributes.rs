@@ -0,0 +1,99 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn short_and_long() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// Be noisy
+        #[clapme(short = 'v')]
+        verbose: bool,
+        #[clapme(short = 'n', long = "num-atoms")]
+        n: u32,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("-v, --verbose "));
+    assert!(Opt::help_message("foo").contains("-n, --num-atoms <INT>"));
+
+    assert_eq!(Opt { verbose: true, n: 7 },
+               Opt::from_iter(&["", "-v", "-n", "7"]).unwrap());
+    assert_eq!(Opt { verbose: false, n: 7 },
+               Opt::from_iter(&["", "--num-atoms", "7"]).unwrap());
+    assert!(Opt::from_iter(&["", "--n", "7"]).is_err());
+}
+
+#[test]
+fn long_ignores_prefix() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Inner {
+        #[clapme(long = "width")]
+        well_width: f64,
+        depth: f64,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        well: Inner,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--width "));
+    assert!(Opt::help_message("foo").contains("--well-depth "));
+
+    assert_eq!(Opt { well: Inner { well_width: 1.0, depth: 2.0 } },
+               Opt::from_iter(&["", "--width", "1", "--well-depth", "2"]).unwrap());
+}
+
+#[test]
+fn rename_and_aliases() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Inner {
+        x: f64,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(rename = "pos")]
+        position: Inner,
+        #[clapme(alias = "dir", aliases("folder", "d"))]
+        directory: String,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--pos-x "));
+    assert!(!Opt::help_message("foo").contains("--folder"));
+
+    for flag in &["--directory", "--dir", "--folder", "--d"] {
+        assert_eq!(Opt { position: Inner { x: 1.0 }, directory: "here".to_string() },
+                   Opt::from_iter(&["", "--pos-x", "1", flag, "here"]).unwrap());
+    }
+}
+
+#[test]
+fn rename_all() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    #[clapme(rename_all = "snake_case")]
+    struct Opt {
+        well_width: f64,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--well_width "));
+
+    #[derive(ClapMe, PartialEq, Debug)]
+    #[clapme(rename_all = "lowercase")]
+    enum Mode {
+        FastMode,
+        /// Renaming a single variant wins over rename_all.
+        #[clapme(rename = "safe")]
+        SafeMode,
+    }
+    println!("help: {}", Mode::help_message("foo"));
+    assert_eq!(Mode::FastMode, Mode::from_iter(&["", "--fastmode"]).unwrap());
+    assert_eq!(Mode::SafeMode, Mode::from_iter(&["", "--safe"]).unwrap());
+}
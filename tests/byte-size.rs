@@ -0,0 +1,66 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::{ClapMe, ByteSize};
+
+#[test]
+fn units() {
+    println!("help: {}", ByteSize::help_message("foo"));
+    assert!(ByteSize::help_message("foo").contains("<SIZE>"));
+
+    assert_eq!(ByteSize(10_000_000), ByteSize::from_iter(&["", "10MB"]).unwrap());
+    assert_eq!(ByteSize(4 << 30), ByteSize::from_iter(&["", "4GiB"]).unwrap());
+    assert_eq!(ByteSize(512_000), ByteSize::from_iter(&["", "512k"]).unwrap());
+    assert_eq!(ByteSize(512 << 10), ByteSize::from_iter(&["", "512Ki"]).unwrap());
+    assert_eq!(ByteSize(1536), ByteSize::from_iter(&["", "1.5KiB"]).unwrap());
+    assert_eq!(ByteSize(4096), ByteSize::from_iter(&["", "4096"]).unwrap());
+    assert_eq!(ByteSize(4096), ByteSize::from_iter(&["", "4096B"]).unwrap());
+    assert_eq!(ByteSize(2_000_000), ByteSize::from_iter(&["", "2 mb"]).unwrap());
+    assert_eq!(ByteSize(1_000_000), ByteSize::from_iter(&["", "1e6"]).unwrap());
+}
+
+#[test]
+fn invalid() {
+    assert!(ByteSize::from_iter(&["", "10 parsecs"]).is_err());
+    assert!(ByteSize::from_iter(&["", "0.5"]).is_err());
+    assert!(ByteSize::from_iter(&["", "MB"]).is_err());
+    assert!(ByteSize::from_iter(&["", "100000EiB"]).is_err());
+    assert!(ByteSize::from_iter(&["", "--", "-1k"]).is_err());
+}
+
+#[test]
+fn display() {
+    assert_eq!("0B", ByteSize(0).to_string());
+    assert_eq!("1023B", ByteSize(1023).to_string());
+    assert_eq!("10MB", ByteSize(10_000_000).to_string());
+    assert_eq!("4GiB", ByteSize(4 << 30).to_string());
+    assert_eq!("1000KiB", ByteSize(1_024_000).to_string());
+    for &x in &[0, 1, 1000, 1024, 10_000_000, 3 << 40, u64::max_value()] {
+        assert_eq!(ByteSize(x), ByteSize(x).to_string().parse().unwrap());
+    }
+}
+
+#[test]
+fn default_in_help() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The size of the cache
+        #[clapme(default_value = "64MiB")]
+        cache: ByteSize,
+        buffers: Vec<ByteSize>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("The size of the cache [default: 64MiB]"));
+    assert_eq!(Opt { cache: ByteSize(64 << 20), buffers: vec![] },
+               Opt::from_iter(&[""]).unwrap());
+    let x = Opt { cache: ByteSize(1000), buffers: vec![ByteSize(1 << 20), ByteSize(5)] };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
@@ -0,0 +1,72 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+use std::collections::{BTreeSet, HashSet, VecDeque};
+
+#[test]
+fn sets_and_deques() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The tags to use
+        tags: HashSet<String>,
+        ids: BTreeSet<u32>,
+        queue: VecDeque<f64>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--tags <STRING>"));
+    assert!(Opt::help_message("foo").contains("--ids <INT>"));
+    assert!(Opt::help_message("foo").contains("--queue <FLOAT>"));
+
+    assert_eq!(Opt { tags: HashSet::new(), ids: BTreeSet::new(), queue: VecDeque::new() },
+               Opt::from_iter(&[""]).unwrap());
+    let x = Opt::from_iter(&["", "--tags", "a", "--ids", "3", "--tags", "b",
+                             "--ids", "1", "--ids", "3", "--queue", "2", "--queue", "1/2"]).unwrap();
+    assert_eq!(x.tags, ["a", "b"].iter().map(|s| s.to_string()).collect());
+    assert_eq!(x.ids.into_iter().collect::<Vec<_>>(), vec![1, 3]);
+    assert_eq!(x.queue, vec![2.0, 0.5].into_iter().collect::<VecDeque<_>>());
+
+    assert!(Opt::from_iter(&["", "--ids", "-1"]).is_err());
+}
+
+#[test]
+fn duplicates() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(duplicates = "warn")]
+        tags: BTreeSet<String>,
+        #[clapme(duplicates = "error")]
+        ids: HashSet<u32>,
+    }
+    let x = Opt::from_iter(&["", "--tags", "a", "--tags", "a"]).unwrap();
+    assert_eq!(x.tags.len(), 1);
+
+    let e = Opt::from_iter(&["", "--ids", "1", "--ids", "1e0"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.flag, "ids");
+    assert_eq!(e.value, Some("1e0".to_string()));
+    assert!(Opt::from_iter(&["", "--ids", "1", "--ids", "2"]).is_ok());
+}
+
+#[test]
+fn to_args() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        tags: BTreeSet<String>,
+        queue: VecDeque<std::path::PathBuf>,
+    }
+    let x = Opt {
+        tags: ["x", "y"].iter().map(|s| s.to_string()).collect(),
+        queue: vec!["/tmp".into(), "b".into(), "/tmp".into()].into_iter().collect(),
+    };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
@@ -0,0 +1,49 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::{ClapMe, Count};
+
+#[test]
+fn count() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// Print more
+        #[clapme(short = 'v')]
+        verbose: Count,
+        quiet: Count,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("-v, --verbose    Print more"));
+
+    assert_eq!(Opt { verbose: Count(0), quiet: Count(0) },
+               Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { verbose: Count(3), quiet: Count(1) },
+               Opt::from_iter(&["", "-vvv", "--quiet"]).unwrap());
+    assert_eq!(Opt { verbose: Count(3), quiet: Count(2) },
+               Opt::from_iter(&["", "-v", "--quiet", "--verbose", "-v", "--quiet"]).unwrap());
+
+    let x = Opt { verbose: Count(2), quiet: Count(0) };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn count_in_substruct() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Log {
+        verbose: Count,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        log: Log,
+    }
+    assert_eq!(Opt { log: Log { verbose: Count(2) } },
+               Opt::from_iter(&["", "--log-verbose", "--log-verbose"]).unwrap());
+}
@@ -0,0 +1,161 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn default_values() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The temperature
+        #[clapme(default = 0.5)]
+        temperature: f64,
+        #[clapme(default = "\"world\".to_string()")]
+        name: String,
+        #[clapme(default)]
+        seeds: Vec<u64>,
+        #[clapme(default)]
+        count: u32,
+        other: i32,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("The temperature [default: 0.5]"));
+    assert!(Opt::help_message("foo").contains("[default: world]"));
+    assert!(Opt::help_message("foo").lines()
+            .any(|l| l.contains("--count") && l.contains("[default: 0]")));
+    assert!(!Opt::help_message("foo").contains("to_string"));
+
+    assert_eq!(
+        Opt {
+            temperature: 0.5,
+            name: "world".to_string(),
+            seeds: Vec::new(),
+            count: 0,
+            other: 3,
+        },
+        Opt::from_iter(&["", "--other", "3"]).unwrap());
+
+    assert_eq!(
+        Opt {
+            temperature: 1.0,
+            name: "hello".to_string(),
+            seeds: vec![1],
+            count: 7,
+            other: 3,
+        },
+        Opt::from_iter(&["", "--other", "3", "--temperature", "1", "--name", "hello",
+                         "--seeds", "1", "--count", "7"]).unwrap());
+
+    assert!(Opt::from_iter(&[""]).is_err());
+}
+
+#[test]
+fn default_struct() {
+    #[derive(ClapMe, PartialEq, Debug, Default)]
+    struct Inner {
+        x: f64,
+        y: f64,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(default)]
+        position: Inner,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+
+    assert_eq!(Opt { position: Inner { x: 0.0, y: 0.0 } },
+               Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { position: Inner { x: 1.0, y: 2.0 } },
+               Opt::from_iter(&["", "--position-x", "1", "--position-y", "2"]).unwrap());
+    assert!(Opt::from_iter(&["", "--position-x", "1"]).is_err());
+}
+
+#[test]
+fn default_value() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The timeout
+        #[clapme(default_value = "1m30s")]
+        timeout: std::time::Duration,
+        #[clapme(default_value = "1/4")]
+        fraction: f64,
+        #[clapme(default_value = "-3")]
+        offset: i32,
+        #[clapme(default_value = "1 -2")]
+        origin: (f64, f64),
+        #[clapme(default_value = "hello world")]
+        greeting: String,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("The timeout [default: 1m30s]"));
+    assert!(Opt::help_message("foo").contains("[default: 1/4]"));
+    assert!(Opt::help_message("foo").contains("[default: 1 -2]"));
+
+    assert_eq!(
+        Opt { timeout: std::time::Duration::from_secs(90), fraction: 0.25, offset: -3,
+              origin: (1.0, -2.0), greeting: "hello world".to_string() },
+        Opt::from_iter(&[""]).unwrap());
+    assert_eq!(
+        Opt { timeout: std::time::Duration::from_secs(1), fraction: 0.25, offset: 7,
+              origin: (0.0, 0.0), greeting: "hi".to_string() },
+        Opt::from_iter(&["", "--timeout", "1s", "--offset", "7", "--origin", "0", "0",
+                         "--greeting", "hi"]).unwrap());
+}
+
+#[test]
+fn invalid_default_value() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(default_value = "many")]
+        count: u32,
+        #[clapme(default_value = "1 x")]
+        origin: (f64, f64),
+    }
+    let e = Opt::from_iter(&["", "--origin", "0", "0"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.kind, clapme::ClapMeErrorKind::InvalidValue);
+    assert_eq!(e.flag, "count");
+    let e = Opt::from_iter(&["", "--count", "3"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.flag, "origin");
+    assert!(e.to_string().contains("\"x\""));
+    assert_eq!(Opt { count: 3, origin: (0.0, 0.0) },
+               Opt::from_iter(&["", "--count", "3", "--origin", "0", "0"]).unwrap());
+}
+
+#[test]
+fn default_help() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The timeout
+        #[clapme(default = "std::time::Duration::from_secs(150)")]
+        timeout: std::time::Duration,
+        /// The points
+        #[clapme(default = "vec![1, 2]")]
+        points: Vec<u32>,
+        /// Be quiet
+        #[clapme(default)]
+        quiet: bool,
+        /// Be loud
+        #[clapme(default = true)]
+        loud: bool,
+        /// The label
+        #[clapme(default)]
+        label: Option<String>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("The timeout [default: 150s]"));
+    assert!(Opt::help_message("foo").contains("The points [default: 1 2]"));
+    assert!(Opt::help_message("foo").contains("Be quiet [default: false]"));
+    assert!(Opt::help_message("foo").contains("Be loud [default: true]"));
+    assert!(Opt::help_message("foo").contains("The label\n"));
+    assert!(Opt::help_message("foo").contains("--no-loud"));
+}
@@ -0,0 +1,78 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn delimited() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(delimiter = ',')]
+        ids: Vec<u32>,
+        #[clapme(delimiter = ":")]
+        path: Vec<std::path::PathBuf>,
+        names: Vec<String>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+
+    assert_eq!(Opt { ids: vec![1, 2, 3], path: vec![], names: vec![] },
+               Opt::from_iter(&["", "--ids", "1,2,3"]).unwrap());
+    assert_eq!(Opt { ids: vec![1, 2, 3], path: vec![], names: vec![] },
+               Opt::from_iter(&["", "--ids", "1", "--ids", "2,3"]).unwrap());
+    assert_eq!(Opt { ids: vec![], path: vec!["/bin".into(), "/usr/bin".into()],
+                     names: vec!["a,b".to_string()] },
+               Opt::from_iter(&["", "--path", "/bin:/usr/bin", "--names", "a,b"]).unwrap());
+    let e = Opt::from_iter(&["", "--ids", "1,x,3"]).unwrap_err();
+    println!("error: {}", e);
+    assert!(e.to_string().contains("\"x\""));
+
+    let x = Opt { ids: vec![4, 5], path: vec!["/a".into()], names: vec!["a,b".to_string()] };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn number_of_values() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(delimiter = ',', min_values = 2, max_values = 3)]
+        point: Vec<f64>,
+    }
+    assert!(Opt::from_iter(&[""]).is_err());
+    assert_eq!(Opt { point: vec![1.0, 2.0] },
+               Opt::from_iter(&["", "--point", "1,2"]).unwrap());
+    assert_eq!(Opt { point: vec![1.0, 2.0, 3.0] },
+               Opt::from_iter(&["", "--point", "1", "--point", "2,3"]).unwrap());
+    assert!(Opt::from_iter(&["", "--point", "1"]).is_err());
+    assert!(Opt::from_iter(&["", "--point", "1,2,3,4"]).is_err());
+}
+
+#[test]
+fn min_values_when_optional() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(min_values = 2)]
+        ids: Vec<u32>,
+        #[clapme(max_values = 2)]
+        names: Vec<String>,
+    }
+    let e = Opt::from_iter(&[""]).unwrap_err();
+    println!("error: {}", e);
+    assert!(e.to_string().contains("--ids"));
+    assert_eq!(Opt { ids: vec![1, 2], names: vec![] },
+               Opt::from_iter(&["", "--ids", "1", "--ids", "2"]).unwrap());
+
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Outer {
+        inner: Option<Opt>,
+    }
+    assert!(Outer::from_iter(&[""]).is_ok());
+}
@@ -0,0 +1,61 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn multiline_doc_comment() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The temperature of the
+        /// system.
+        temperature: f64,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("The temperature of the system."));
+}
+
+#[test]
+fn multiparagraph_doc_comment() {
+    /// A simulation.
+    ///
+    /// This runs a simulation for a long time.
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The temperature.
+        ///
+        /// This is given in Kelvin, and must
+        /// be positive.
+        ///
+        /// A third paragraph.
+        temperature: f64,
+        /// The number of steps.
+        ///
+        /// More about steps.
+        #[clapme(default = 10)]
+        steps: u64,
+    }
+    let short_help = Opt::from_iter(&["", "-h"]).unwrap_err().to_string();
+    let long_help = Opt::from_iter(&["", "--help"]).unwrap_err().to_string();
+    println!("short help: {}", short_help);
+    println!("long help: {}", long_help);
+
+    // clap shows the long about of the program even with -h.
+    assert!(short_help.contains("A simulation."));
+    assert!(short_help.contains("The temperature."));
+    assert!(!short_help.contains("Kelvin"));
+    assert!(short_help.contains("The number of steps. [default: 10]"));
+
+    assert!(long_help.contains("This runs a simulation for a long time."));
+    assert!(long_help.contains("The temperature.\n"));
+    assert!(long_help.contains("This is given in Kelvin, and must be positive.\n"));
+    assert!(long_help.contains("A third paragraph."));
+    assert!(long_help.contains("More about steps. [default: 10]"));
+}
@@ -0,0 +1,73 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+use std::time::Duration;
+
+#[test]
+fn units() {
+    println!("help: {}", Duration::help_message("foo"));
+    assert!(Duration::help_message("foo").contains("<DURATION>"));
+
+    assert_eq!(Duration::from_secs(90), Duration::from_iter(&["", "90s"]).unwrap());
+    assert_eq!(Duration::from_secs(90), Duration::from_iter(&["", "90"]).unwrap());
+    assert_eq!(Duration::from_secs(330), Duration::from_iter(&["", "5m30s"]).unwrap());
+    assert_eq!(Duration::from_secs(5400), Duration::from_iter(&["", "1.5h"]).unwrap());
+    assert_eq!(Duration::from_millis(250), Duration::from_iter(&["", "250ms"]).unwrap());
+    assert_eq!(Duration::from_micros(3), Duration::from_iter(&["", "3us"]).unwrap());
+    assert_eq!(Duration::from_secs(2*86400), Duration::from_iter(&["", "2d"]).unwrap());
+    assert_eq!(Duration::from_secs(5400), Duration::from_iter(&["", "1h 30min"]).unwrap());
+    assert_eq!(Duration::from_millis(1500), Duration::from_iter(&["", "1.5"]).unwrap());
+}
+
+#[test]
+fn expressions() {
+    assert_eq!(Duration::from_secs(120), Duration::from_iter(&["", "2*60s"]).unwrap());
+    assert_eq!(Duration::from_secs(1000), Duration::from_iter(&["", "1e3s"]).unwrap());
+    assert_eq!(Duration::from_secs(2), Duration::from_iter(&["", "sqrt(4)s"]).unwrap());
+}
+
+#[test]
+fn invalid() {
+    assert!(Duration::from_iter(&["", "5 parsecs"]).is_err());
+    assert!(Duration::from_iter(&["", "--", "-5s"]).is_err());
+    assert!(Duration::from_iter(&["", "s"]).is_err());
+    assert!(Duration::from_iter(&[""]).is_err());
+}
+
+#[test]
+fn in_struct() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// How long to wait
+        timeout: Duration,
+        checkpoints: Vec<Duration>,
+        #[clapme(default = "Duration::from_secs(60)")]
+        interval: Duration,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--timeout <DURATION>"));
+    assert_eq!(Opt {
+                   timeout: Duration::from_secs(10),
+                   checkpoints: vec![Duration::from_secs(60), Duration::from_millis(1)],
+                   interval: Duration::from_secs(60),
+               },
+               Opt::from_iter(&["", "--timeout", "10s", "--checkpoints", "1m",
+                                "--checkpoints", "1ms"]).unwrap());
+    let x = Opt {
+        timeout: Duration::new(3, 5),
+        checkpoints: vec![Duration::from_secs(7200), Duration::from_secs(0)],
+        interval: Duration::from_millis(1500),
+    };
+    println!("args: {:?}", x.to_args());
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
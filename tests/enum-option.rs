@@ -0,0 +1,85 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[derive(ClapMe, PartialEq, Debug)]
+enum Mode {
+    Fast,
+    Safe,
+}
+
+#[derive(ClapMe, PartialEq, Debug)]
+enum Action {
+    Greet { hello: String, times: u32 },
+    Leave(String),
+    Wave,
+}
+
+#[test]
+fn option_unit_enum() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        mode: Option<Mode>,
+        verbose: bool,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert_eq!(Opt { mode: None, verbose: false }, Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { mode: None, verbose: true }, Opt::from_iter(&["", "--verbose"]).unwrap());
+    assert_eq!(Opt { mode: Some(Mode::Fast), verbose: false },
+               Opt::from_iter(&["", "--mode-fast"]).unwrap());
+    assert_eq!(Opt { mode: Some(Mode::Safe), verbose: false },
+               Opt::from_iter(&["", "--mode-safe"]).unwrap());
+    assert!(Opt::from_iter(&["", "--mode-fast", "--mode-safe"]).is_err());
+    for x in vec![Opt { mode: None, verbose: true }, Opt { mode: Some(Mode::Safe), verbose: false }] {
+        assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    }
+}
+
+#[test]
+fn option_enum_with_data() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        action: Option<Action>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert_eq!(Opt { action: None }, Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { action: Some(Action::Greet { hello: "you".to_string(), times: 2 }) },
+               Opt::from_iter(&["", "--action-greet-hello", "you",
+                                "--action-greet-times", "2"]).unwrap());
+    assert_eq!(Opt { action: Some(Action::Leave("now".to_string())) },
+               Opt::from_iter(&["", "--action-leave", "now"]).unwrap());
+    assert_eq!(Opt { action: Some(Action::Wave) },
+               Opt::from_iter(&["", "--action-wave"]).unwrap());
+
+    // A variant must be given in full, and only one variant may be given.
+    assert!(Opt::from_iter(&["", "--action-greet-hello", "you"]).is_err());
+    assert!(Opt::from_iter(&["", "--action-greet-times", "2"]).is_err());
+    assert!(Opt::from_iter(&["", "--action-leave", "now", "--action-wave"]).is_err());
+    assert!(Opt::from_iter(&["", "--action-greet-hello", "you", "--action-greet-times", "2",
+                             "--action-wave"]).is_err());
+
+    for x in vec![Opt { action: None },
+                  Opt { action: Some(Action::Greet { hello: "a".to_string(), times: 1 }) },
+                  Opt { action: Some(Action::Leave("b".to_string())) },
+                  Opt { action: Some(Action::Wave) }] {
+        assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    }
+}
+
+#[test]
+fn top_level_option_enum() {
+    assert_eq!(None, <Option<Mode>>::from_iter(&[""]).unwrap());
+    assert_eq!(Some(Mode::Fast), <Option<Mode>>::from_iter(&["", "--fast"]).unwrap());
+    assert_eq!(None, <Option<Action>>::from_iter(&[""]).unwrap());
+    assert_eq!(Some(Action::Wave), <Option<Action>>::from_iter(&["", "--wave"]).unwrap());
+}
@@ -0,0 +1,63 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+use std::collections::BTreeSet;
+
+#[derive(ClapMe, PartialEq, Eq, PartialOrd, Ord, Debug)]
+enum Mode {
+    Fast,
+    Safe,
+    #[clapme(rename = "extra-careful")]
+    Careful,
+}
+
+#[test]
+fn vec_of_enum() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The modes to use
+        mode: Vec<Mode>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--mode <MODE>..."));
+    assert!(Opt::help_message("foo").contains("The modes to use"));
+
+    assert_eq!(Opt { mode: vec![] }, Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { mode: vec![Mode::Fast, Mode::Safe, Mode::Fast] },
+               Opt::from_iter(&["", "--mode", "fast", "--mode", "safe", "--mode=fast"]).unwrap());
+    assert_eq!(Opt { mode: vec![Mode::Careful] },
+               Opt::from_iter(&["", "--mode", "extra-careful"]).unwrap());
+    let e = Opt::from_iter(&["", "--mode", "slow"]).unwrap_err();
+    println!("error: {}", e);
+    assert!(e.to_string().contains("expected one of fast, safe, extra-careful"));
+
+    let x = Opt { mode: vec![Mode::Safe, Mode::Careful] };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn set_of_enum() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(delimiter = ',')]
+        modes: BTreeSet<Mode>,
+    }
+    assert_eq!(Opt { modes: vec![Mode::Fast, Mode::Safe].into_iter().collect() },
+               Opt::from_iter(&["", "--modes", "safe,fast"]).unwrap());
+}
+
+#[test]
+fn enum_still_gives_flags() {
+    assert_eq!(Mode::Safe, Mode::from_iter(&["", "--safe"]).unwrap());
+    assert_eq!(Mode::Careful, Mode::from_iter(&["", "--extra-careful"]).unwrap());
+}
@@ -182,3 +182,22 @@ fn enum_with_nested_underscore_variant() {
 
     assert!(EnumOpt::from_iter(&["", "--say-hello=David", "--say-goodbye=Goliath"]).is_err());
 }
+
+#[test]
+fn enum_variant_without_required_field() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum EnumOpt {
+        A { x: Option<i32>, z: Option<i32> },
+        B { y: i32 },
+    }
+    println!("help: {}", EnumOpt::help_message("foo"));
+    assert_eq!(EnumOpt::B { y: 1 }, EnumOpt::from_iter(&["", "--b-y", "1"]).unwrap());
+    assert_eq!(EnumOpt::A { x: Some(2), z: None },
+               EnumOpt::from_iter(&["", "--a-x", "2"]).unwrap());
+    assert_eq!(EnumOpt::A { x: None, z: Some(3) },
+               EnumOpt::from_iter(&["", "--a-z", "3"]).unwrap());
+    assert!(EnumOpt::from_iter(&[""]).is_err());
+    assert!(EnumOpt::from_iter(&["", "--a-x", "2", "--b-y", "1"]).is_err());
+    let x = EnumOpt::A { x: Some(4), z: Some(5) };
+    assert_eq!(x, EnumOpt::from_iter(x.to_args()).unwrap());
+}
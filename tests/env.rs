@@ -0,0 +1,93 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn env_attribute() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The temperature
+        #[clapme(env = "CLAPME_TEST_TEMPERATURE")]
+        temperature: f64,
+        #[clapme(env = "CLAPME_TEST_VERBOSE")]
+        verbose: bool,
+        #[clapme(env = "CLAPME_TEST_NAME")]
+        name: Option<String>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("The temperature [env: CLAPME_TEST_TEMPERATURE]"));
+
+    assert!(Opt::from_iter(&[""]).is_err());
+    std::env::set_var("CLAPME_TEST_TEMPERATURE", "300");
+    std::env::set_var("CLAPME_TEST_VERBOSE", "yes");
+    assert_eq!(
+        Opt { temperature: 300.0, verbose: true, name: None },
+        Opt::from_iter(&[""]).unwrap());
+    assert_eq!(
+        Opt { temperature: 5.0, verbose: true, name: Some("hello".to_string()) },
+        Opt::from_iter(&["", "--temperature", "5", "--name", "hello"]).unwrap());
+
+    std::env::set_var("CLAPME_TEST_VERBOSE", "0");
+    assert_eq!(
+        Opt { temperature: 300.0, verbose: false, name: None },
+        Opt::from_iter(&[""]).unwrap());
+    assert_eq!(
+        Opt { temperature: 300.0, verbose: true, name: None },
+        Opt::from_iter(&["", "--verbose"]).unwrap());
+
+    std::env::set_var("CLAPME_TEST_VERBOSE", "maybe");
+    assert!(Opt::from_iter(&[""]).is_err());
+}
+
+#[test]
+fn env_prefix() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Well {
+        width: f64,
+        depth: f64,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Sys {
+        well: Well,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    #[clapme(env_prefix = "CLAPMETEST")]
+    struct Opt {
+        sys: Sys,
+        seeds: Vec<u64>,
+        #[clapme(env = "CLAPMETEST_OTHER_NAME")]
+        name: String,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("[env: CLAPMETEST_SYS_WELL_WIDTH]"));
+    assert!(Opt::help_message("foo").contains("[env: CLAPMETEST_OTHER_NAME]"));
+
+    std::env::set_var("CLAPMETEST_SYS_WELL_WIDTH", "2");
+    std::env::set_var("CLAPMETEST_SEEDS", "7");
+    std::env::set_var("CLAPMETEST_OTHER_NAME", "bob");
+    assert!(Opt::from_iter(&[""]).is_err());
+    assert_eq!(
+        Opt {
+            sys: Sys { well: Well { width: 2.0, depth: 1.0 } },
+            seeds: vec![7],
+            name: "bob".to_string(),
+        },
+        Opt::from_iter(&["", "--sys-well-depth", "1"]).unwrap());
+    assert_eq!(
+        Opt {
+            sys: Sys { well: Well { width: 3.0, depth: 1.0 } },
+            seeds: vec![1, 2],
+            name: "alice".to_string(),
+        },
+        Opt::from_iter(&["", "--sys-well-depth", "1", "--sys-well-width", "3",
+                         "--seeds", "1", "--seeds", "2", "--name", "alice"]).unwrap());
+}
@@ -0,0 +1,49 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate clapme;
+
+use clapme::{ClapMe, ClapMeError, ClapMeErrorKind};
+use clapme::clap::ErrorKind;
+
+#[test]
+fn error_kinds() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        arg: i32,
+    }
+    assert_eq!(Opt::from_iter(&[""]).unwrap_err().kind,
+               ClapMeErrorKind::Clap(ErrorKind::MissingRequiredArgument));
+    assert_eq!(Opt::from_iter(&["", "--arg", "hello"]).unwrap_err().kind,
+               ClapMeErrorKind::Clap(ErrorKind::ValueValidation));
+    assert_eq!(Opt::from_iter(&["", "--help"]).unwrap_err().kind,
+               ClapMeErrorKind::Clap(ErrorKind::HelpDisplayed));
+    assert!(Opt::from_iter(&["", "--help"]).unwrap_err().to_string().contains("--arg"));
+}
+
+#[test]
+fn enum_without_variant() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum EnumOpt {
+        First,
+        Second,
+    }
+    let matches = clapme::clap::App::new("foo").get_matches_from(&[""]);
+    let e = EnumOpt::from_clap("", &matches).unwrap_err();
+    assert_eq!(e.kind, ClapMeErrorKind::MissingArgument);
+    assert_eq!(None, <Option<EnumOpt>>::from_clap("", &matches).unwrap());
+}
+
+#[test]
+fn invalid_value_message() {
+    let e = ClapMeError::invalid_value("count", "seven", "not a number");
+    assert_eq!(e.kind, ClapMeErrorKind::InvalidValue);
+    assert_eq!(e.flag, "count");
+    assert_eq!(e.value, Some("seven".to_string()));
+    assert_eq!(e.to_string(), "Invalid value for '--count' \"seven\": not a number");
+}
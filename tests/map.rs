@@ -0,0 +1,87 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+use std::collections::{BTreeMap, HashMap};
+
+#[test]
+fn hash_map() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// A tuning knob
+        define: HashMap<String, f64>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--define <STRING=FLOAT>"));
+    assert!(Opt::help_message("foo").contains("A tuning knob"));
+
+    let mut define = HashMap::new();
+    assert_eq!(Opt { define: define.clone() }, Opt::from_iter(&[""]).unwrap());
+    define.insert("alpha".to_string(), 0.5);
+    define.insert("beta".to_string(), 2.0);
+    assert_eq!(Opt { define: define.clone() },
+               Opt::from_iter(&["", "--define", "alpha=1/2", "--define=beta=2"]).unwrap());
+    let x = Opt { define: define };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn btree_map() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        weights: BTreeMap<u32, String>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--weights <INT=STRING>"));
+    let mut weights = BTreeMap::new();
+    weights.insert(1, "a=b".to_string());
+    weights.insert(2, "".to_string());
+    assert_eq!(Opt { weights: weights.clone() },
+               Opt::from_iter(&["", "--weights", "1=a=b", "--weights", "2="]).unwrap());
+    let x = Opt { weights: weights };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn invalid_pairs() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        define: BTreeMap<u32, f64>,
+    }
+    assert!(Opt::from_iter(&["", "--define", "1"]).is_err());
+    let e = Opt::from_iter(&["", "--define", "x=1"]).unwrap_err();
+    println!("error: {}", e);
+    assert!(e.to_string().contains("invalid key \"x\""));
+    let e = Opt::from_iter(&["", "--define", "1=x"]).unwrap_err();
+    println!("error: {}", e);
+    assert!(e.to_string().contains("invalid value \"x\""));
+}
+
+#[test]
+fn duplicate_keys() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        define: BTreeMap<String, u32>,
+        #[clapme(duplicates = "error")]
+        strict: BTreeMap<String, u32>,
+    }
+    let mut define = BTreeMap::new();
+    define.insert("a".to_string(), 2);
+    assert_eq!(Opt { define: define, strict: BTreeMap::new() },
+               Opt::from_iter(&["", "--define", "a=1", "--define", "a=2"]).unwrap());
+    let e = Opt::from_iter(&["", "--strict", "a=1", "--strict", "a=2"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.kind, clapme::ClapMeErrorKind::InvalidValue);
+    assert_eq!(e.flag, "strict");
+    assert_eq!(e.value, Some("a".to_string()));
+    assert!(Opt::from_iter(&["", "--strict", "a=1", "--strict", "b=2"]).is_ok());
+}
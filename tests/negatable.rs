@@ -0,0 +1,89 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn default_true() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// Use color
+        #[clapme(default = true)]
+        color: bool,
+        #[clapme(default = false)]
+        fancy: bool,
+        verbose: bool,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--no-color"));
+    assert!(Opt::help_message("foo").contains("Use color [default: true]"));
+    assert!(!Opt::help_message("foo").contains("<BOOL>"));
+    assert!(!Opt::help_message("foo").contains("--no-fancy"));
+    assert!(!Opt::help_message("foo").contains("--no-verbose"));
+
+    assert_eq!(Opt { color: true, fancy: false, verbose: false },
+               Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { color: false, fancy: true, verbose: false },
+               Opt::from_iter(&["", "--no-color", "--fancy"]).unwrap());
+    assert_eq!(Opt { color: true, fancy: false, verbose: false },
+               Opt::from_iter(&["", "--color"]).unwrap());
+
+    for x in vec![Opt { color: false, fancy: true, verbose: false },
+                  Opt { color: true, fancy: false, verbose: true }] {
+        assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    }
+}
+
+#[test]
+fn last_one_wins() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(negatable)]
+        color: bool,
+    }
+    assert_eq!(Opt { color: false }, Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { color: true }, Opt::from_iter(&["", "--color"]).unwrap());
+    assert_eq!(Opt { color: false }, Opt::from_iter(&["", "--no-color"]).unwrap());
+    assert_eq!(Opt { color: false },
+               Opt::from_iter(&["", "--color", "--no-color"]).unwrap());
+    assert_eq!(Opt { color: true },
+               Opt::from_iter(&["", "--no-color", "--color"]).unwrap());
+    assert_eq!(Opt { color: true },
+               Opt::from_iter(&["", "--color", "--no-color", "--color"]).unwrap());
+}
+
+#[test]
+fn nested_negatable() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Output {
+        #[clapme(default = true)]
+        color: bool,
+        pager: bool,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        output: Output,
+        #[clapme(negatable)]
+        input: Output,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert_eq!(Opt {
+                   output: Output { color: true, pager: false },
+                   input: Output { color: true, pager: false },
+               },
+               Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt {
+                   output: Output { color: false, pager: true },
+                   input: Output { color: true, pager: false },
+               },
+               Opt::from_iter(&["", "--no-output-color", "--output-pager",
+                                "--input-pager", "--no-input-pager"]).unwrap());
+    assert!(Opt::from_iter(&["", "--no-output-pager"]).is_err());
+}
@@ -81,3 +81,79 @@ fn optional_option() {
 
     assert!(SuperOpt::from_iter(&["", "--arg2", "7", "--other", "hello"]).is_err());
 }
+
+#[test]
+fn boxed_and_shared() {
+    use std::rc::Rc;
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        arg: i32,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct SuperOpt {
+        boxed: Box<Opt>,
+        shared: Option<Rc<Opt>>,
+    }
+    println!("help: {}", SuperOpt::help_message("foo"));
+    assert!(SuperOpt::help_message("foo").contains("--boxed-arg"));
+    assert!(SuperOpt::help_message("foo").contains("--shared-arg"));
+
+    assert_eq!(
+        SuperOpt { boxed: Box::new(Opt { arg: 7 }), shared: None },
+        SuperOpt::from_iter(&["", "--boxed-arg", "7"]).unwrap());
+    let x = SuperOpt { boxed: Box::new(Opt { arg: 1 }), shared: Some(Rc::new(Opt { arg: 2 })) };
+    assert_eq!(x, SuperOpt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn recursive() {
+    // A recursive type is given flags down to a limited depth.
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Tree {
+        value: i32,
+        child: Option<Box<Tree>>,
+    }
+    println!("help: {}", Tree::help_message("foo"));
+    assert!(Tree::help_message("foo").contains("--child-child-value"));
+    assert_eq!(Tree { value: 1, child: None }, Tree::from_iter(&["", "--value", "1"]).unwrap());
+    let x = Tree { value: 1, child: Some(Box::new(Tree { value: 2, child: None })) };
+    let args: Vec<String> = x.to_clap_args("").into_iter()
+        .map(|a| a.into_string().unwrap()).collect();
+    assert_eq!(args, vec!["--value=1", "--child-value=2"]);
+    assert_eq!(x, Tree::from_iter(x.to_args()).unwrap());
+    assert_eq!(x, Tree::from_iter(&["", "--value", "1", "--child-value", "2"]).unwrap());
+
+    let deep = Tree::from_iter(&["", "--value=0", "--child-value=1", "--child-child-value=2",
+                                 "--child-child-child-value=3"]).unwrap();
+    assert_eq!(deep.child.unwrap().child.unwrap().child.unwrap().value, 3);
+    assert!(Tree::from_iter(&["", "--value=0", "--child-child-child-child-value=4"]).is_err());
+
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Node {
+        name: String,
+        left: Option<Box<Node>>,
+        right: Option<Box<Node>>,
+    }
+    println!("help: {}", Node::help_message("foo"));
+    let leaf = |name: &str| Node { name: name.to_string(), left: None, right: None };
+    let x = Node {
+        name: "a".to_string(),
+        left: Some(Box::new(Node { right: Some(Box::new(leaf("c"))), ..leaf("b") })),
+        right: Some(Box::new(Node { left: Some(Box::new(leaf("e"))), ..leaf("d") })),
+    };
+    assert_eq!(x, Node::from_iter(x.to_args()).unwrap());
+
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Family {
+        name: String,
+        children: Vec<Family>,
+    }
+    let x = Family {
+        name: "a".to_string(),
+        children: vec![Family { name: "b".to_string(), children: vec![] },
+                       Family { name: "c".to_string(),
+                                children: vec![Family { name: "d".to_string(),
+                                                        children: vec![] }] }],
+    };
+    assert_eq!(x, Family::from_iter(x.to_args()).unwrap());
+}
@@ -0,0 +1,81 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn tristate() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// Use color
+        color: Option<bool>,
+        verbose: bool,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--color=<BOOL>"));
+    assert!(Opt::help_message("foo").contains("Use color"));
+
+    assert_eq!(Opt { color: None, verbose: false },
+               Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt { color: Some(true), verbose: false },
+               Opt::from_iter(&["", "--color"]).unwrap());
+    assert_eq!(Opt { color: Some(true), verbose: true },
+               Opt::from_iter(&["", "--color=true", "--verbose"]).unwrap());
+    assert_eq!(Opt { color: Some(false), verbose: false },
+               Opt::from_iter(&["", "--color=false"]).unwrap());
+    assert_eq!(Opt { color: Some(true), verbose: false },
+               Opt::from_iter(&["", "--color=yes"]).unwrap());
+    assert_eq!(Opt { color: Some(false), verbose: false },
+               Opt::from_iter(&["", "--color=no"]).unwrap());
+    assert!(Opt::from_iter(&["", "--color=maybe"]).is_err());
+}
+
+#[test]
+fn does_not_take_next_argument() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        color: Option<bool>,
+        #[clapme(positional)]
+        file: String,
+    }
+    assert_eq!(Opt { color: Some(true), file: "false".to_string() },
+               Opt::from_iter(&["", "--color", "false"]).unwrap());
+}
+
+#[test]
+fn nested() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Color {
+        fancy: Option<bool>,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        color: Color,
+    }
+    assert_eq!(Opt { color: Color { fancy: Some(false) } },
+               Opt::from_iter(&["", "--color-fancy=false"]).unwrap());
+    assert_eq!(Opt { color: Color { fancy: None } },
+               Opt::from_iter(&[""]).unwrap());
+}
+
+#[test]
+fn to_args() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        color: Option<bool>,
+    }
+    for x in vec![Opt { color: None },
+                  Opt { color: Some(true) },
+                  Opt { color: Some(false) }] {
+        assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    }
+}
@@ -0,0 +1,247 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+use std::io::Write;
+
+#[derive(ClapMe, PartialEq, Debug)]
+struct Well {
+    width: f64,
+    depth: f64,
+}
+
+#[derive(ClapMe, PartialEq, Debug)]
+struct Params {
+    /// The temperature
+    temperature: f64,
+    verbose: bool,
+    seeds: Vec<u64>,
+    well: Well,
+    name: Option<String>,
+}
+
+fn temp_file(name: &str) -> std::path::PathBuf {
+    std::env::temp_dir().join(format!("clapme-{}-{}", std::process::id(), name))
+}
+
+#[test]
+fn save_and_load() {
+    let path = temp_file("save-and-load");
+    let x = Params {
+        temperature: 300.0,
+        verbose: true,
+        seeds: vec![1, 2],
+        well: Well { width: 1.5, depth: -2.0 },
+        name: None,
+    };
+    x.save_params(&path).unwrap();
+    let mut contents = String::new();
+    std::io::Read::read_to_string(&mut std::fs::File::open(&path).unwrap(),
+                                  &mut contents).unwrap();
+    println!("{}", contents);
+    assert!(contents.contains("--temperature=300\n"));
+    assert!(contents.contains("--well-width=1.5\n"));
+    assert_eq!(x, Params::load_params(&path).unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn hand_edited() {
+    let path = temp_file("hand-edited");
+    write!(std::fs::File::create(&path).unwrap(),
+           "# a comment\n\n--temperature=2*150\n  --well-width=1\n--well-depth=2\n--seeds=7\n")
+        .unwrap();
+    assert_eq!(Params {
+                   temperature: 300.0,
+                   verbose: false,
+                   seeds: vec![7],
+                   well: Well { width: 1.0, depth: 2.0 },
+                   name: None,
+               },
+               Params::load_params(&path).unwrap());
+    std::fs::remove_file(&path).unwrap();
+    assert!(Params::load_params(&path).is_err());
+}
+
+#[test]
+fn load_with_overrides() {
+    let path = temp_file("load-with-overrides");
+    Params {
+        temperature: 300.0,
+        verbose: false,
+        seeds: vec![1, 2],
+        well: Well { width: 1.5, depth: 2.0 },
+        name: Some("old".to_string()),
+    }.save_params(&path).unwrap();
+    let path_str = path.to_str().unwrap();
+
+    assert_eq!(Params {
+                   temperature: 10.0,
+                   verbose: true,
+                   seeds: vec![5],
+                   well: Well { width: 1.5, depth: 2.0 },
+                   name: Some("old".to_string()),
+               },
+               Params::from_iter_with_params(&["", "--load-params", path_str,
+                                               "--temperature", "10", "--verbose",
+                                               "--seeds=5"]).unwrap());
+
+    let saved = temp_file("load-with-overrides-saved");
+    let saved_str = saved.to_str().unwrap();
+    let y = Params::from_iter_with_params(&["", "--well-depth", "3", "--well-width", "4",
+                                            "--temperature", "1",
+                                            "--save-params", saved_str]).unwrap();
+    assert_eq!(y, Params::load_params(&saved).unwrap());
+    assert_eq!(y, Params::from_iter_with_params(&["", &format!("--load-params={}", saved_str)])
+               .unwrap());
+
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&saved).unwrap();
+    assert!(Params::from_iter_with_params(&["", "--load-params", path_str]).is_err());
+}
+
+#[test]
+fn load_with_subcommand() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Command {
+        Build {
+            jobs: u32,
+            release: bool,
+        },
+        Test {
+            filter: Option<String>,
+        },
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        verbose: bool,
+        #[clapme(subcommand)]
+        command: Command,
+    }
+    let path = temp_file("load-with-subcommand");
+    let path_str = path.to_str().unwrap();
+    let x = Opt { verbose: true, command: Command::Build { jobs: 4, release: false } };
+    x.save_params(&path).unwrap();
+
+    assert_eq!(x, Opt::from_iter_with_params(&["", "--load-params", path_str]).unwrap());
+    assert_eq!(Opt { verbose: true, command: Command::Build { jobs: 8, release: true } },
+               Opt::from_iter_with_params(&["", "--load-params", path_str,
+                                            "build", "--jobs", "8", "--release"]).unwrap());
+    assert_eq!(Opt { verbose: true, command: Command::Test { filter: None } },
+               Opt::from_iter_with_params(&["", "--load-params", path_str, "test"]).unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn load_with_tuple_and_positional() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(short = 'o', alias = "start")]
+        origin: (f64, f64),
+        #[clapme(short = 'n')]
+        name: String,
+        #[clapme(positional)]
+        input: String,
+    }
+    let path = temp_file("load-with-tuple-and-positional");
+    let path_str = path.to_str().unwrap();
+    let x = Opt { origin: (1.0, -2.0), name: "a".to_string(), input: "in.dat".to_string() };
+    x.save_params(&path).unwrap();
+
+    assert_eq!(x, Opt::from_iter_with_params(&["", "--load-params", path_str]).unwrap());
+    assert_eq!(Opt { origin: (5.0, 6.0), name: "b".to_string(), input: "other".to_string() },
+               Opt::from_iter_with_params(&["", "--load-params", path_str, "--origin", "5", "6",
+                                            "-n", "b", "other"]).unwrap());
+    assert_eq!(Opt { origin: (-5.0, 6.0), name: "c".to_string(), input: "in.dat".to_string() },
+               Opt::from_iter_with_params(&["", "-o", "-5", "6", "-nc",
+                                            "--load-params", path_str]).unwrap());
+    assert_eq!(Opt { origin: (0.0, 0.0), name: "a".to_string(), input: "in.dat".to_string() },
+               Opt::from_iter_with_params(&["", "--load-params", path_str,
+                                            "--start", "0", "0"]).unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn load_without_file() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        temperature: Option<f64>,
+    }
+    assert!(Opt::from_iter_with_params(&["", "--load-params"]).is_err());
+    assert!(Opt::from_iter_with_params(&["", "--temperature", "3", "--load-params"]).is_err());
+    assert!(Opt::from_iter_with_params(&["", "--load-params="]).is_err());
+}
+
+#[test]
+fn load_with_positional_and_subcommand() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Command {
+        Run {
+            steps: u32,
+        },
+        Check,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(positional)]
+        input: String,
+        #[clapme(subcommand)]
+        command: Command,
+    }
+    let path = temp_file("load-with-positional-and-subcommand");
+    let path_str = path.to_str().unwrap();
+    let x = Opt { input: "in.dat".to_string(), command: Command::Run { steps: 10 } };
+    x.save_params(&path).unwrap();
+
+    assert_eq!(x, Opt::load_params(&path).unwrap());
+    assert_eq!(x, Opt::from_iter_with_params(&["", "--load-params", path_str]).unwrap());
+    assert_eq!(Opt { input: "other.dat".to_string(), command: Command::Run { steps: 5 } },
+               Opt::from_iter_with_params(&["", "--load-params", path_str, "other.dat",
+                                            "run", "--steps", "5"]).unwrap());
+    assert_eq!(Opt { input: "in.dat".to_string(), command: Command::Check },
+               Opt::from_iter_with_params(&["", "--load-params", path_str, "check"]).unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn load_with_collection_and_subcommand() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Command {
+        Run {
+            steps: u32,
+        },
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        ids: Vec<u32>,
+        #[clapme(positional)]
+        input: Option<String>,
+        #[clapme(subcommand)]
+        command: Command,
+    }
+    let path = temp_file("load-with-collection-and-subcommand");
+    let path_str = path.to_str().unwrap();
+    let x = Opt { ids: vec![1, 2], input: None, command: Command::Run { steps: 10 } };
+    x.save_params(&path).unwrap();
+
+    assert_eq!(x, Opt::from_iter_with_params(&["", "--load-params", path_str]).unwrap());
+    assert_eq!(Opt { ids: vec![9], input: None, command: Command::Run { steps: 10 } },
+               Opt::from_iter_with_params(&["", "--load-params", path_str, "--ids", "9"])
+               .unwrap());
+    assert_eq!(Opt { ids: vec![9, 8], input: None, command: Command::Run { steps: 10 } },
+               Opt::from_iter_with_params(&["", "--ids", "9", "8", "--load-params", path_str])
+               .unwrap());
+    assert_eq!(Opt { ids: vec![3], input: Some("in.dat".to_string()),
+                     command: Command::Run { steps: 10 } },
+               Opt::from_iter_with_params(&["", "--load-params", path_str, "--ids=3",
+                                            "in.dat"]).unwrap());
+    std::fs::remove_file(&path).unwrap();
+}
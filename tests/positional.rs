@@ -0,0 +1,80 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+use std::path::PathBuf;
+
+#[test]
+fn positional_fields() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The input file
+        #[clapme(positional)]
+        input: PathBuf,
+        verbose: bool,
+        /// The output file
+        #[clapme(positional)]
+        output: Option<PathBuf>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("foo [FLAGS] <input> [output]"));
+    assert!(!Opt::help_message("foo").contains("--input"));
+
+    assert_eq!(
+        Opt { input: PathBuf::from("a"), verbose: false, output: None },
+        Opt::from_iter(&["", "a"]).unwrap());
+    assert_eq!(
+        Opt { input: PathBuf::from("a"), verbose: true, output: Some(PathBuf::from("b")) },
+        Opt::from_iter(&["", "a", "--verbose", "b"]).unwrap());
+    assert!(Opt::from_iter(&[""]).is_err());
+    assert!(Opt::from_iter(&["", "--input", "a"]).is_err());
+    assert!(Opt::from_iter(&["", "a", "b", "c"]).is_err());
+
+    let x = Opt { input: PathBuf::from("-a"), verbose: true, output: Some(PathBuf::from("b")) };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn trailing_varargs() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(positional)]
+        command: String,
+        n: Option<u32>,
+        #[clapme(positional)]
+        args: Vec<String>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert_eq!(
+        Opt { command: "ls".to_string(), n: None, args: vec![] },
+        Opt::from_iter(&["", "ls"]).unwrap());
+    assert_eq!(
+        Opt {
+            command: "ls".to_string(),
+            n: Some(1),
+            args: vec!["a".to_string(), "b".to_string()],
+        },
+        Opt::from_iter(&["", "ls", "a", "--n", "1", "b"]).unwrap());
+    assert_eq!(
+        Opt {
+            command: "ls".to_string(),
+            n: Some(1),
+            args: vec!["-l".to_string(), "--n".to_string(), "2".to_string()],
+        },
+        Opt::from_iter(&["", "--n", "1", "ls", "--", "-l", "--n", "2"]).unwrap());
+
+    let x = Opt {
+        command: "ls".to_string(),
+        n: Some(3),
+        args: vec!["-l".to_string(), "x".to_string()],
+    };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
@@ -88,3 +88,45 @@ fn simple_f64_many_ways() {
     assert_eq!(1e300_f64.sqrt(),
                <f64>::from_iter(&["","sqrt(1e300)"]).unwrap());
 }
+
+#[test]
+fn simple_u8_and_i8() {
+    assert_eq!(7, u8::from_iter(&["","7"]).unwrap());
+    assert_eq!(200, u8::from_iter(&["","2e2"]).unwrap());
+    assert!(u8::from_iter(&["","256"]).is_err());
+    assert!(u8::from_iter(&["","--","-1"]).is_err());
+    assert_eq!(-7, i8::from_iter(&["","--","-7"]).unwrap());
+    assert!(i8::from_iter(&["","128"]).is_err());
+}
+
+#[test]
+fn simple_char() {
+    println!("help: {}", char::help_message("foo"));
+    assert!(char::help_message("foo").contains("<CHAR>"));
+    assert_eq!('x', char::from_iter(&["","x"]).unwrap());
+    assert!(char::from_iter(&["","xy"]).is_err());
+}
+
+#[test]
+fn nonzero() {
+    use std::num::{NonZeroU32, NonZeroI64};
+    assert_eq!(NonZeroU32::new(7).unwrap(),
+               NonZeroU32::from_iter(&["","7"]).unwrap());
+    assert_eq!(NonZeroI64::new(-3).unwrap(),
+               NonZeroI64::from_iter(&["","--","-3"]).unwrap());
+    let e = NonZeroU32::from_iter(&["","0"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.kind, clapme::ClapMeErrorKind::InvalidValue);
+    assert!(e.to_string().contains("must not be zero"));
+    assert_eq!(None, <Option<NonZeroU32>>::from_iter(&[""]).unwrap());
+}
+
+#[test]
+fn pointers() {
+    use std::rc::Rc;
+    use std::sync::Arc;
+    assert_eq!(Box::new(7), <Box<u32>>::from_iter(&["","7"]).unwrap());
+    assert_eq!(Rc::new(7), <Rc<u32>>::from_iter(&["","7"]).unwrap());
+    assert_eq!(Arc::new(7), <Arc<u32>>::from_iter(&["","7"]).unwrap());
+    assert_eq!(None, <Option<Box<String>>>::from_iter(&[""]).unwrap());
+}
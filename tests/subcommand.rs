@@ -0,0 +1,116 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn subcommand_enum() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    #[clapme(subcommand)]
+    enum Tool {
+        /// Build the project
+        Build {
+            release: bool,
+            jobs: Option<u32>,
+        },
+        /// Remove build products
+        Clean,
+        RunTests(String),
+    }
+    println!("help: {}", Tool::help_message("foo"));
+    assert!(Tool::help_message("foo").contains("SUBCOMMANDS"));
+    assert!(Tool::help_message("foo").contains("Build the project"));
+
+    assert_eq!(
+        Tool::Build { release: true, jobs: None },
+        Tool::from_iter(&["", "build", "--release"]).unwrap());
+    assert_eq!(
+        Tool::Build { release: false, jobs: Some(4) },
+        Tool::from_iter(&["", "build", "--jobs", "4"]).unwrap());
+    assert_eq!(
+        Tool::Clean,
+        Tool::from_iter(&["", "clean"]).unwrap());
+    assert_eq!(
+        Tool::RunTests("hello".to_string()),
+        Tool::from_iter(&["", "run-tests", "hello"]).unwrap());
+
+    assert!(Tool::from_iter(&[""]).is_err());
+    assert!(Tool::from_iter(&["", "--release"]).is_err());
+    assert!(Tool::from_iter(&["", "clean", "--release"]).is_err());
+    assert!(Tool::from_iter(&["", "build", "clean"]).is_err());
+}
+
+#[test]
+fn subcommand_field() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Remote {
+        Add { name: String, url: String },
+        Remove { name: String },
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Command {
+        /// Show the status
+        Status { short: bool },
+        Remote(Remote),
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Git {
+        verbose: bool,
+        #[clapme(subcommand)]
+        command: Command,
+    }
+    println!("help: {}", Git::help_message("git"));
+
+    assert_eq!(
+        Git { verbose: true, command: Command::Status { short: false } },
+        Git::from_iter(&["", "--verbose", "status"]).unwrap());
+    assert_eq!(
+        Git {
+            verbose: false,
+            command: Command::Remote(Remote::Add {
+                name: "origin".to_string(),
+                url: "http://example.com".to_string(),
+            }),
+        },
+        Git::from_iter(&["", "remote", "add", "--name", "origin",
+                         "--url", "http://example.com"]).unwrap());
+    assert_eq!(
+        Git {
+            verbose: false,
+            command: Command::Remote(Remote::Remove { name: "origin".to_string() }),
+        },
+        Git::from_iter(&["", "remote", "remove", "--name", "origin"]).unwrap());
+
+    assert!(Git::from_iter(&["", "--verbose"]).is_err());
+    assert!(Git::from_iter(&["", "remote"]).is_err());
+    assert!(Git::from_iter(&["", "remote", "--remove-name", "origin"]).is_err());
+}
+
+#[test]
+fn optional_subcommand() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    #[clapme(subcommand)]
+    enum Command {
+        Start,
+        Stop,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        name: String,
+        command: Option<Command>,
+    }
+    assert_eq!(
+        Opt { name: "x".to_string(), command: None },
+        Opt::from_iter(&["", "--name", "x"]).unwrap());
+    assert_eq!(
+        Opt { name: "x".to_string(), command: Some(Command::Stop) },
+        Opt::from_iter(&["", "--name", "x", "stop"]).unwrap());
+}
@@ -0,0 +1,78 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[derive(ClapMe, PartialEq, Debug)]
+struct Opt {
+    temperature: f64,
+    n: u32,
+    name: String,
+}
+
+fn opt(temperature: f64, n: u32, name: &str) -> Opt {
+    Opt { temperature: temperature, n: n, name: name.to_string() }
+}
+
+#[test]
+fn no_sweep() {
+    assert_eq!(vec![opt(1.0, 2, "a,b")],
+               Opt::sweep_from_iter(&["", "--temperature", "1", "--n", "2",
+                                      "--name", "a,b"]).unwrap());
+}
+
+#[test]
+fn list_sweep() {
+    assert_eq!(vec![opt(1.0, 2, "x"), opt(1.0, 3, "x"),
+                    opt(0.5, 2, "x"), opt(0.5, 3, "x"),
+                    opt(2.0, 2, "x"), opt(2.0, 3, "x")],
+               Opt::sweep_from_iter(&["", "--temperature", "1, 1/2, max(1,2)",
+                                      "--n=2,3", "--name", "x"]).unwrap());
+}
+
+#[test]
+fn range_sweep() {
+    assert_eq!(vec![opt(0.0, 1, "x"), opt(0.5, 1, "x"), opt(1.0, 1, "x"),
+                    opt(0.0, 3, "x"), opt(0.5, 3, "x"), opt(1.0, 3, "x"),
+                    opt(0.0, 10, "x"), opt(0.5, 10, "x"), opt(1.0, 10, "x")],
+               Opt::sweep_from_iter(&["", "--n", "1:3:2,10",
+                                      "--temperature", "0:1:0.5",
+                                      "--name", "x"]).unwrap());
+    assert_eq!(vec![opt(3.0, 1, "x"), opt(2.0, 1, "x"), opt(1.0, 1, "x")],
+               Opt::sweep_from_iter(&["", "--n", "1", "--temperature", "3:1:-1",
+                                      "--name", "x"]).unwrap());
+}
+
+#[test]
+fn bad_sweep() {
+    assert!(Opt::sweep_from_iter(&["", "--n", "1,x", "--temperature", "1",
+                                   "--name", "x"]).is_err());
+    assert!(Opt::sweep_from_iter(&["", "--n", "1:3:-1", "--temperature", "1",
+                                   "--name", "x"]).is_err());
+    assert!(Opt::sweep_from_iter(&["", "--n", "1.5,2", "--temperature", "1",
+                                   "--name", "x"]).is_err());
+}
+
+#[test]
+fn short_and_alias_sweep() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(short = 't', alias = "temp")]
+        temperature: f64,
+        #[clapme(short = 'n')]
+        n: u32,
+    }
+    let expected = vec![Opt { temperature: 1.0, n: 2 }, Opt { temperature: 1.0, n: 3 },
+                        Opt { temperature: 2.0, n: 2 }, Opt { temperature: 2.0, n: 3 }];
+    assert_eq!(expected, Opt::sweep_from_iter(&["", "-t", "1,2", "-n2,3"]).unwrap());
+    assert_eq!(expected, Opt::sweep_from_iter(&["", "-t1,2", "-n=2,3"]).unwrap());
+    assert_eq!(expected, Opt::sweep_from_iter(&["", "--temp=1,2", "--n", "2,3"]).unwrap());
+    assert_eq!(expected, Opt::sweep_from_iter(&["", "--temp", "1:2", "-n", "2:3"]).unwrap());
+}
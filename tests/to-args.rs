@@ -0,0 +1,135 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+extern crate clapme;
+
+use clapme::ClapMe;
+use std::ffi::OsString;
+
+#[test]
+fn struct_round_trip() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Vec2d {
+        x: f64,
+        y: f64,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        verbose: bool,
+        quiet: bool,
+        temperature: f64,
+        n: i32,
+        name: String,
+        dir: std::path::PathBuf,
+        seeds: Vec<u64>,
+        maybe: Option<String>,
+        never: Option<String>,
+        position: Vec2d,
+        _flat: std::marker::PhantomData<u8>,
+        #[clapme(short = 'c', long = "count")]
+        count: u32,
+        #[clapme(default = 5)]
+        other: u32,
+    }
+    let x = Opt {
+        verbose: true,
+        quiet: false,
+        temperature: 0.1,
+        n: -3,
+        name: "a b --c".to_string(),
+        dir: std::path::PathBuf::from("/tmp"),
+        seeds: vec![1, 2, 3],
+        maybe: Some("".to_string()),
+        never: None,
+        position: Vec2d { x: 1e-7, y: -1e300 },
+        _flat: std::marker::PhantomData,
+        count: 4,
+        other: 7,
+    };
+    println!("args: {:?}", x.to_args());
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    assert_eq!(&x.to_args()[1..],
+               &[OsString::from("--verbose"), OsString::from("--temperature=0.1"),
+                 OsString::from("--n=-3"), OsString::from("--name=a b --c"),
+                 OsString::from("--dir=/tmp"),
+                 OsString::from("--seeds=1"), OsString::from("--seeds=2"),
+                 OsString::from("--seeds=3"), OsString::from("--maybe="),
+                 OsString::from("--position-x=0.0000001"),
+                 OsString::from(format!("--position-y={}", -1e300)),
+                 OsString::from("--count=4"), OsString::from("--other=7")][..]);
+}
+
+#[test]
+fn non_finite_round_trip() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        max: f64,
+        min: f32,
+        step: f64,
+    }
+    let x = Opt { max: std::f64::INFINITY, min: std::f32::NEG_INFINITY, step: std::f64::NAN };
+    println!("args: {:?}", x.to_args());
+    let y = Opt::from_iter(x.to_args()).unwrap();
+    assert_eq!((x.max, x.min), (y.max, y.min));
+    assert!(y.step.is_nan());
+}
+
+#[test]
+fn enum_round_trip() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Choice {
+        First { a: String, b: Option<u16> },
+        Second(f64),
+        Third,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        choice: Choice,
+        other: Option<Choice>,
+    }
+    let values = vec![
+        Opt { choice: Choice::First { a: "x".to_string(), b: None }, other: None },
+        Opt { choice: Choice::Second(2.5), other: Some(Choice::Third) },
+        Opt { choice: Choice::Third, other: Some(Choice::First { a: "y".to_string(), b: Some(3) }) },
+    ];
+    for x in values {
+        println!("args: {:?}", x.to_args());
+        assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    }
+}
+
+#[test]
+fn subcommand_round_trip() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Remote {
+        Add { name: String },
+        Prune,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Command {
+        Build { release: bool, jobs: Option<u32> },
+        Remote(Remote),
+        Clean,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(subcommand)]
+        command: Command,
+        verbose: bool,
+    }
+    let values = vec![
+        Opt { command: Command::Build { release: true, jobs: Some(2) }, verbose: true },
+        Opt { command: Command::Remote(Remote::Add { name: "x".to_string() }), verbose: false },
+        Opt { command: Command::Remote(Remote::Prune), verbose: true },
+        Opt { command: Command::Clean, verbose: false },
+    ];
+    for x in values {
+        println!("args: {:?}", x.to_args());
+        assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    }
+}
@@ -36,3 +36,74 @@ fn unit_struct() {
     assert_eq!(Opt,
                Opt::from_iter(&[""]).unwrap());
 }
+
+#[derive(ClapMe, PartialEq, Debug)]
+struct Range(f64, f64);
+
+#[test]
+fn multi_field_tuple_struct() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The range to scan
+        range: Range,
+        window: Option<Range>,
+        verbose: bool,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--range <FLOAT> <FLOAT>"));
+    assert!(Opt::help_message("foo").contains("The range to scan"));
+
+    assert_eq!(Opt { range: Range(0.0, 10.0), window: None, verbose: true },
+               Opt::from_iter(&["", "--range", "0", "10", "--verbose"]).unwrap());
+    assert_eq!(Opt { range: Range(-1.0, 0.5), window: Some(Range(2.0, 3.0)), verbose: false },
+               Opt::from_iter(&["", "--range", "-1", "1/2", "--window", "2", "3"]).unwrap());
+    assert!(Opt::from_iter(&[""]).is_err());
+    assert!(Opt::from_iter(&["", "--range", "0"]).is_err());
+    assert!(Opt::from_iter(&["", "--range", "0", "1", "2"]).is_err());
+
+    let e = Opt::from_iter(&["", "--range", "0", "x"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.flag, "range");
+    assert!(e.to_string().contains("in position 2"));
+
+    let x = Opt { range: Range(-3.0, 1e10), window: Some(Range(0.0, -0.25)), verbose: false };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn multi_field_tuple_at_top_level() {
+    println!("help: {}", Range::help_message("foo"));
+    assert_eq!(Range(1.0, 2.0), Range::from_iter(&["", "1", "2"]).unwrap());
+    assert!(Range::from_iter(&["", "1"]).is_err());
+}
+
+#[test]
+fn multi_field_tuple_variant() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    enum Shape {
+        /// A circle with its radius
+        Circle(f64),
+        /// A rectangle with its width and height
+        Rectangle(f64, f64),
+        /// A box with a label and its size
+        Labeled(String, u32, u32),
+    }
+    println!("help: {}", Shape::help_message("foo"));
+    assert!(Shape::help_message("foo").contains("--rectangle <FLOAT> <FLOAT>"));
+    assert!(Shape::help_message("foo").contains("--labeled <STRING> <INT> <INT>"));
+    assert!(Shape::help_message("foo").contains("A rectangle with its width and height"));
+
+    assert_eq!(Shape::Rectangle(2.0, 3.0),
+               Shape::from_iter(&["", "--rectangle", "2", "3"]).unwrap());
+    assert_eq!(Shape::Labeled("box".to_string(), 1, 2),
+               Shape::from_iter(&["", "--labeled", "box", "1", "2"]).unwrap());
+    assert!(Shape::from_iter(&["", "--rectangle", "2", "3", "--circle", "1"]).is_err());
+    let e = Shape::from_iter(&["", "--labeled", "box", "1", "2.5"]).unwrap_err();
+    println!("error: {}", e);
+    assert!(e.to_string().contains("in position 3"));
+
+    for x in vec![Shape::Circle(1.0), Shape::Rectangle(2.0, 3.0),
+                  Shape::Labeled("a".to_string(), 4, 5)] {
+        assert_eq!(x, Shape::from_iter(x.to_args()).unwrap());
+    }
+}
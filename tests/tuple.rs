@@ -0,0 +1,132 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[test]
+fn pair_and_triple() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        /// The corner of the box
+        corner: (f64, f64),
+        origin: Option<(i32, i32, i32)>,
+        label: (String, u32),
+        verbose: bool,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--corner <FLOAT> <FLOAT>"));
+    assert!(Opt::help_message("foo").contains("--origin <INT> <INT> <INT>"));
+    assert!(Opt::help_message("foo").contains("--label <STRING> <INT>"));
+    assert!(Opt::help_message("foo").contains("The corner of the box"));
+
+    assert_eq!(Opt { corner: (1.0, 0.5), origin: None, label: ("a".to_string(), 3), verbose: true },
+               Opt::from_iter(&["", "--corner", "1", "1/2", "--label", "a", "3",
+                                "--verbose"]).unwrap());
+    assert_eq!(Opt { corner: (-1.0, 2.0), origin: Some((0, -5, 7)), label: ("b".to_string(), 0),
+                     verbose: false },
+               Opt::from_iter(&["", "--origin", "0", "-5", "7", "--corner", "-1", "2",
+                                "--label", "b", "0"]).unwrap());
+    assert!(Opt::from_iter(&["", "--label", "a", "3"]).is_err());
+    assert!(Opt::from_iter(&["", "--corner", "1", "--label", "a", "3"]).is_err());
+
+    let e = Opt::from_iter(&["", "--corner", "1", "2", "--label", "a", "-3"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.flag, "label");
+    assert!(e.to_string().contains("in position 2"));
+
+    let x = Opt { corner: (-3.0, 1e10), origin: Some((1, -2, 3)), label: ("c d".to_string(), 4),
+                  verbose: true };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn array() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        origin: [f64; 3],
+        size: Option<[u32; 2]>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--origin <FLOAT> <FLOAT> <FLOAT>"));
+
+    assert_eq!(Opt { origin: [0.0, 0.0, 1.0], size: None },
+               Opt::from_iter(&["", "--origin", "0", "0", "1"]).unwrap());
+    assert_eq!(Opt { origin: [0.0, -1.0, 1.0], size: Some([3, 4]) },
+               Opt::from_iter(&["", "--size", "3", "4", "--origin", "0", "-1", "1"]).unwrap());
+    assert!(Opt::from_iter(&["", "--origin", "0", "0"]).is_err());
+
+    let e = Opt::from_iter(&["", "--origin", "0", "0", "1", "--size", "3", "x"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.flag, "size");
+    assert!(e.to_string().contains("in position 2"));
+
+    let x = Opt { origin: [0.25, -7.0, 1e-3], size: Some([5, 6]) };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn delimited() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        #[clapme(delimiter = ',')]
+        origin: [i32; 3],
+        #[clapme(delimiter = ':')]
+        pair: Option<(String, f64)>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert_eq!(Opt { origin: [1, 2, 3], pair: Some(("a".to_string(), 0.5)) },
+               Opt::from_iter(&["", "--origin=1,2,3", "--pair", "a:1/2"]).unwrap());
+    assert_eq!(Opt { origin: [1, 2, 3], pair: None },
+               Opt::from_iter(&["", "--origin", "1", "2", "3"]).unwrap());
+    assert!(Opt::from_iter(&["", "--origin=1,2"]).is_err());
+    let e = Opt::from_iter(&["", "--origin=1,2,x"]).unwrap_err();
+    println!("error: {}", e);
+    assert!(e.to_string().contains("in position 3"));
+}
+
+#[test]
+fn at_top_level() {
+    println!("help: {}", <(u8, char)>::help_message("foo"));
+    assert_eq!((7, 'x'), <(u8, char)>::from_iter(&["", "7", "x"]).unwrap());
+    assert_eq!([1, 2], <[u8; 2]>::from_iter(&["", "1", "2"]).unwrap());
+    assert!(<[u8; 2]>::from_iter(&["", "1"]).is_err());
+}
+
+#[test]
+fn empty_array() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        nothing: [u32; 0],
+        verbose: bool,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(!Opt::help_message("foo").contains("--nothing"));
+    assert_eq!(Opt { nothing: [], verbose: true },
+               Opt::from_iter(&["", "--verbose"]).unwrap());
+    assert!(Opt::from_iter(&["", "--nothing"]).is_err());
+    let x = Opt { nothing: [], verbose: false };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn followed_by_flags() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        corner: (f64, f64),
+        origin: [i32; 2],
+        verbose: bool,
+    }
+    assert_eq!(Opt { corner: (-1.0, -2.0), origin: [-3, 4], verbose: true },
+               Opt::from_iter(&["", "--corner", "-1", "-2", "--origin", "-3", "4",
+                                "--verbose"]).unwrap());
+    assert!(Opt::from_iter(&["", "--corner", "-1", "--origin", "-3", "4"]).is_err());
+}
@@ -0,0 +1,74 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[derive(ClapMe, PartialEq, Debug, Clone, Copy)]
+#[clapme(value_enum)]
+enum Color {
+    /// The color of blood
+    Red,
+    Green,
+    /// The color of the sky
+    #[clapme(rename = "sky-blue")]
+    Blue,
+}
+
+#[derive(ClapMe, PartialEq, Debug)]
+struct Opt {
+    /// The color to paint
+    color: Color,
+    #[clapme(default_value = "green")]
+    background: Color,
+    highlight: Option<Color>,
+}
+
+#[test]
+fn value_enum() {
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--color <red|green|sky-blue>"));
+    assert!(Opt::help_message("foo").contains("The color to paint"));
+    assert!(!Opt::help_message("foo").contains("--red"));
+
+    assert_eq!(Opt { color: Color::Red, background: Color::Green, highlight: None },
+               Opt::from_iter(&["", "--color", "red"]).unwrap());
+    assert_eq!(Opt { color: Color::Blue, background: Color::Red, highlight: Some(Color::Green) },
+               Opt::from_iter(&["", "--color", "sky-blue", "--background", "RED",
+                                "--highlight=Green"]).unwrap());
+    assert!(Opt::from_iter(&[""]).is_err());
+    assert!(Opt::from_iter(&["", "--color", "blue"]).is_err());
+    assert!(Opt::from_iter(&["", "--color", "red", "--color", "green"]).is_err());
+
+    let x = Opt { color: Color::Blue, background: Color::Red, highlight: Some(Color::Red) };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn variant_docs_in_long_help() {
+    let help = Opt::from_iter(&["", "--help"]).unwrap_err().to_string();
+    println!("long help: {}", help);
+    assert!(help.contains("red: The color of blood"));
+    assert!(help.contains("sky-blue: The color of the sky"));
+    assert!(!help.contains("green:"));
+    assert!(!Opt::help_message("foo").contains("The color of blood"));
+}
+
+#[test]
+fn vec_of_value_enum() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        colors: Vec<Color>,
+    }
+    assert!(Opt::help_message("foo").contains("--colors <red|green|sky-blue>..."));
+    assert_eq!(Opt { colors: vec![Color::Red, Color::Blue] },
+               Opt::from_iter(&["", "--colors", "Red", "--colors", "sky-blue"]).unwrap());
+}
@@ -0,0 +1,212 @@
+// Copyright 2018 David Roundy <roundyd@physics.oregonstate.edu>
+//
+// Licensed under the Apache License, Version 2.0 <LICENSE-APACHE or
+// http://www.apache.org/licenses/LICENSE-2.0> or the MIT license
+// <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your
+// option. This file may not be copied, modified, or distributed
+// except according to those terms.
+
+#[macro_use]
+extern crate clapme;
+
+use clapme::ClapMe;
+
+#[derive(ClapMe, PartialEq, Debug)]
+struct Point {
+    /// The x coordinate
+    x: f64,
+    /// The y coordinate
+    y: f64,
+    label: Option<String>,
+}
+
+#[derive(ClapMe, PartialEq, Debug)]
+struct Opt {
+    point: Vec<Point>,
+    verbose: bool,
+}
+
+#[test]
+fn grouped_by_position() {
+    println!("help: {}", Opt::help_message("foo"));
+    assert!(Opt::help_message("foo").contains("--point-x <FLOAT>..."));
+    assert!(Opt::help_message("foo").contains("The y coordinate"));
+
+    assert_eq!(Opt { point: vec![], verbose: false }, Opt::from_iter(&[""]).unwrap());
+    assert_eq!(Opt {
+                   point: vec![Point { x: 1.0, y: 2.0, label: None },
+                               Point { x: 3.0, y: 4.0, label: None }],
+                   verbose: true,
+               },
+               Opt::from_iter(&["", "--point-x", "1", "--point-y", "2", "--verbose",
+                                "--point-x", "3", "--point-y", "4"]).unwrap());
+    assert_eq!(Opt {
+                   point: vec![Point { x: 1.0, y: 2.0, label: None },
+                               Point { x: 3.0, y: 4.0, label: Some("b".to_string()) }],
+                   verbose: false,
+               },
+               Opt::from_iter(&["", "--point-x", "1", "--point-y", "2",
+                                "--point-y", "4", "--point-label", "b", "--point-x", "3"]).unwrap());
+}
+
+#[test]
+fn optional_member() {
+    let x = Opt {
+        point: vec![Point { x: 1.0, y: 0.0, label: None },
+                    Point { x: 2.0, y: 0.0, label: Some("b".to_string()) }],
+        verbose: false,
+    };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    assert_eq!(x, Opt::from_iter(&["", "--point-x=1", "--point-y=0", "--point-x=2",
+                                   "--point-y=0", "--point-label=b"]).unwrap());
+    // The label goes with the flags it is given among.
+    assert_eq!(Opt {
+                   point: vec![Point { x: 1.0, y: 0.0, label: Some("b".to_string()) },
+                               Point { x: 2.0, y: 0.0, label: None }],
+                   verbose: false,
+               },
+               Opt::from_iter(&["", "--point-y=0", "--point-x=1", "--point-label=b",
+                                "--point-x=2", "--point-y=0"]).unwrap());
+}
+
+#[test]
+fn members_given_apart() {
+    // Giving all the x values before the y values leaves the first
+    // point without a y value.
+    let e = Opt::from_iter(&["", "--point-x", "1", "--point-x", "3",
+                             "--point-y", "2", "--point-y", "4"]).unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.kind, clapme::ClapMeErrorKind::MissingArgument);
+    assert_eq!(e.flag, "point-y");
+    assert!(e.to_string().contains("group 1 of '--point'"));
+}
+
+#[test]
+fn sibling_flags() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        point: Vec<Point>,
+        point_size: Option<f64>,
+    }
+    assert_eq!(Opt { point: vec![Point { x: 1.0, y: 2.0, label: None }], point_size: Some(3.0) },
+               Opt::from_iter(&["", "--point-x", "1", "--point-size", "3",
+                                "--point-y", "2"]).unwrap());
+}
+
+#[test]
+fn nested_members() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Species {
+        #[clapme(short = 'n')]
+        name: String,
+        position: Point,
+        charged: bool,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        species: Vec<Species>,
+    }
+    println!("help: {}", Opt::help_message("foo"));
+    let x = Opt {
+        species: vec![
+            Species { name: "a".to_string(), position: Point { x: 1.0, y: 2.0, label: None },
+                      charged: true },
+            Species { name: "b".to_string(),
+                      position: Point { x: -1.0, y: 0.0, label: Some("c".to_string()) },
+                      charged: false },
+        ],
+    };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    assert_eq!(x, Opt::from_iter(&["", "--species-name", "a", "--species-position-x", "1",
+                                   "--species-position-y", "2",
+                                   "--species-charged", "--species-position-x=-1",
+                                   "--species-position-y", "0", "--species-position-label", "c",
+                                   "-n", "b"]).unwrap());
+}
+
+#[test]
+fn bool_members() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Species {
+        charged: bool,
+        name: String,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        species: Vec<Species>,
+    }
+    let x = Opt {
+        species: vec![Species { charged: true, name: "a".to_string() },
+                      Species { charged: true, name: "b".to_string() }],
+    };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    assert_eq!(x, Opt::from_iter(&["", "--species-charged", "--species-name", "a",
+                                   "--species-charged", "--species-name", "b"]).unwrap());
+    let y = Opt {
+        species: vec![Species { charged: false, name: "a".to_string() },
+                      Species { charged: true, name: "b".to_string() }],
+    };
+    assert_eq!(y, Opt::from_iter(y.to_args()).unwrap());
+}
+
+#[test]
+fn collection_members() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Species {
+        name: String,
+        tags: Vec<String>,
+        #[clapme(short = 'l')]
+        level: clapme::Count,
+    }
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        species: Vec<Species>,
+    }
+    let x = Opt {
+        species: vec![
+            Species { name: "a".to_string(), tags: vec!["x".to_string(), "y".to_string()],
+                      level: clapme::Count(2) },
+            Species { name: "b".to_string(), tags: vec![], level: clapme::Count(0) },
+            Species { name: "c".to_string(), tags: vec!["z".to_string()],
+                      level: clapme::Count(1) },
+        ],
+    };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+    assert_eq!(x, Opt::from_iter(&["", "--species-name", "a", "--species-tags", "x", "-ll",
+                                   "--species-tags", "y", "--species-name", "b",
+                                   "--species-name", "c", "--species-tags", "z",
+                                   "--species-level"]).unwrap());
+}
+
+#[test]
+fn missing_member() {
+    assert!(Opt::from_iter(&["", "--point-x", "1"]).is_err());
+    let e = Opt::from_iter(&["", "--point-x", "1", "--point-y", "2", "--point-x", "3"])
+        .unwrap_err();
+    println!("error: {}", e);
+    assert_eq!(e.kind, clapme::ClapMeErrorKind::MissingArgument);
+    assert_eq!(e.flag, "point-y");
+    assert!(e.to_string().contains("group 2 of '--point'"));
+}
+
+#[test]
+fn to_args() {
+    let x = Opt {
+        point: vec![Point { x: 1.0, y: 2.0, label: Some("a".to_string()) },
+                    Point { x: -3.0, y: 0.5, label: Some("b".to_string()) }],
+        verbose: true,
+    };
+    assert_eq!(x, Opt::from_iter(x.to_args()).unwrap());
+}
+
+#[test]
+fn newtype_elements() {
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Meters(f64);
+    #[derive(ClapMe, PartialEq, Debug)]
+    struct Opt {
+        lengths: Vec<Meters>,
+    }
+    assert_eq!(Opt { lengths: vec![Meters(1.0), Meters(2.5)] },
+               Opt::from_iter(&["", "--lengths", "1", "--lengths", "5/2"]).unwrap());
+}